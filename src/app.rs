@@ -1,10 +1,35 @@
-use crate::entities::package_info::PackageInfo;
-use crate::repository::HomebrewRepository;
+use crate::collections::CollectionStore;
+use crate::command_line::{self, Command, CompletionSource};
+use crate::config::Config;
+use crate::entities::package_info::{PackageInfo, PackageType};
+use crate::entities::timeline_entry::TimelineEntry;
+use crate::export::{self, ExportFormat};
+use crate::filters::QuickFilters;
+use crate::fuzzy;
+use crate::helpers;
+use crate::keymap::{Action, KeyMode, KeySequence, Keymap};
+use crate::line_editor::LineEditor;
+use crate::notes::NoteStore;
+use crate::operation_log::{self, OperationKind, OperationRecord, RemovedPackage};
+use crate::package_cache::{self, PackageChanges};
+use crate::prompt::{Picker, PickerItem, PickerKind, Prompt, PromptKind};
+use crate::query::Query;
+use crate::repository::{BrewOutput, HomebrewRepository};
+use crate::search_store::{SavedSearch, SearchStore};
+use crate::sort::SortMode;
+use crate::theme::Theme;
+use crate::ui_state::UiState;
 use anyhow::Result;
+use ratatui::layout::Rect;
 use ratatui::widgets::ListState;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
 use std::time::{Duration, Instant};
 
+/// How long the latest uninstall can be undone from the package list
+const UNDO_WINDOW: Duration = Duration::from_secs(10 * 60);
+
 /// Mock update stages for UX testing
 #[derive(Debug, Clone, PartialEq)]
 pub enum UpdateStage {
@@ -27,6 +52,27 @@ pub enum ModalState {
     None,
     UpdateProgress,
     UninstallConfirmation,
+    Help,
+    Prompt(Prompt),
+    Picker(Picker),
+}
+
+/// The screen currently shown in the main area
+#[derive(Debug, Clone, PartialEq)]
+pub enum Screen {
+    Packages,
+    Timeline,
+    History,
+}
+
+/// Screen areas that react to the mouse, recorded while rendering each frame
+#[derive(Debug, Default)]
+pub struct MouseTargets {
+    pub list: Option<Rect>, // Rows of the single-column package list, the timeline or the history
+    pub columns: Vec<Rect>, // Columns of the multi-column package table, left to right
+    pub homepage: Option<Rect>,
+    pub buttons: Vec<(Rect, Action)>, // Modal buttons and the actions they run
+    pub rows: Vec<(Rect, usize)>,     // Rows of the open picker and the positions they show
 }
 
 /// Application state and business logic
@@ -36,11 +82,30 @@ pub struct App {
     pub scroll_offset: usize,
     pub last_interaction: Instant,
     pub should_quit: bool,
-    pub search_query: String,
+    pub search: LineEditor,               // The search prompt
+    pub query: Query,                     // The search query as last parsed successfully
+    pub query_error: Option<String>,      // Why the search query could not be parsed
+    pub search_store: SearchStore,        // Search history and saved searches
+    pub notes: NoteStore,                 // Personal notes about packages
+    pub collections: CollectionStore,     // The user's named collections of packages
+    history_position: Option<usize>,      // Index of the history entry shown in the search prompt
+    history_draft: String,                // The text typed before browsing a history
+    pub command_line: Option<LineEditor>, // The `:` command line, while it is open
+    pub completions: Vec<String>, // Candidates of the command line's last Tab, when ambiguous
     pub filtered_items: Vec<PackageInfo>,
     pub is_searching: bool,
     pub pre_search_selection: Option<usize>, // Track selection before search started
+    pub filters: QuickFilters,
+    pub sort_mode: SortMode,
     pub status_messages: VecDeque<(String, Instant)>,
+    pub config: Config,
+    pub theme: Theme,
+    pub keymap: Keymap,
+    pub pending_keys: KeySequence, // Keys typed so far of an unfinished chord
+    pub help_mode: KeyMode,        // Mode whose bindings the help overlay lists
+    pub help_scroll: usize,        // First binding shown by the help overlay
+    pub help_rows: usize,          // Bindings the help overlay fits, set when it is rendered
+    pub mouse_targets: MouseTargets,
     repository: HomebrewRepository,
     // Multi-column layout state
     pub current_columns: usize,
@@ -54,15 +119,41 @@ pub struct App {
     pub update_stage: UpdateStage,
     pub is_uninstalling: bool,    // Track if this is an uninstall operation
     pub real_update_called: bool, // Track if real update has been called
-    pub pending_uninstall_package: Option<String>, // Package pending uninstall confirmation
+    pub pending_uninstall: Vec<String>, // Packages pending uninstall confirmation
+    pub operation_queue: VecDeque<String>, // Packages waiting for the same operation as the running one
+    pub reinstalling: Option<RemovedPackage>, // The removed package an undo is installing again
+    last_removal: Option<(RemovedPackage, Instant)>, // The latest uninstall, while it can be undone
     // Modal state
     pub modal_state: ModalState,
+    // Screen state
+    pub screen: Screen,
+    pub timeline: Vec<TimelineEntry>,
+    pub timeline_state: ListState,
+    pub operations: Vec<OperationRecord>, // The operation log, newest first
+    pub operations_state: ListState,      // Selection among the operations shown
+    pub operations_filter: Option<String>, // Package the history is narrowed to
+    // Background loading state
+    pub stale_since: Option<u64>, // Timestamp of the cached package list being shown, if not yet refreshed
+    pub refreshed_at: u64,        // Timestamp of the latest package list loaded from Homebrew
+    pub newly_outdated: HashSet<String>, // Packages that became outdated at the latest background refresh
+    pub run_brew_update: bool,           // Whether refreshes run `brew update` first
+    background_load: Option<Receiver<Result<Vec<PackageInfo>>>>,
+    reload_started: Instant, // When the latest background reload started, to schedule the next one
+    operation_finished: Option<Instant>, // When the latest upgrade, install or uninstall finished
 }
 
 impl App {
     /// Creates a new application instance
-    pub fn new(repository: HomebrewRepository) -> Result<Self> {
-        let items = repository.get_all_packages()?;
+    pub fn new(repository: HomebrewRepository, config: Config) -> Result<Self> {
+        let sort_mode = UiState::load().sort_mode;
+        let notes = NoteStore::load()?;
+        let mut items = repository.get_all_packages()?;
+        notes.annotate(&mut items);
+        let collections = CollectionStore::load()?;
+        collections.annotate(&mut items);
+        sort_mode.sort(&mut items);
+        let theme = Theme::for_terminal(&config)?;
+        let keymap = Keymap::new(&config.keys)?;
         let mut app = Self {
             filtered_items: items.clone(),
             items,
@@ -70,10 +161,29 @@ impl App {
             scroll_offset: 0,
             last_interaction: Instant::now(),
             should_quit: false,
-            search_query: String::new(),
+            search: LineEditor::default(),
+            query: Query::default(),
+            query_error: None,
+            search_store: SearchStore::load()?,
+            notes,
+            collections,
+            history_position: None,
+            history_draft: String::new(),
+            command_line: None,
+            completions: Vec::new(),
             is_searching: false,
             pre_search_selection: None,
+            filters: QuickFilters::default(),
+            sort_mode,
             status_messages: VecDeque::new(),
+            config,
+            theme,
+            keymap,
+            pending_keys: Vec::new(),
+            help_mode: KeyMode::Normal,
+            help_scroll: 0,
+            help_rows: usize::MAX,
+            mouse_targets: MouseTargets::default(),
             repository,
             current_columns: 1,
             rows_per_column: 0,
@@ -85,38 +195,186 @@ impl App {
             update_stage: UpdateStage::Idle,
             is_uninstalling: false,
             real_update_called: false,
-            pending_uninstall_package: None,
+            pending_uninstall: Vec::new(),
+            operation_queue: VecDeque::new(),
+            reinstalling: None,
+            last_removal: None,
             modal_state: ModalState::None,
+            screen: Screen::Packages,
+            timeline: Vec::new(),
+            timeline_state: ListState::default(),
+            operations: Vec::new(),
+            operations_state: ListState::default(),
+            operations_filter: None,
+            stale_since: None,
+            refreshed_at: helpers::unix_now(),
+            newly_outdated: HashSet::new(),
+            run_brew_update: true,
+            background_load: None,
+            reload_started: Instant::now(),
+            operation_finished: None,
         };
         app.list_state.select(Some(0));
         Ok(app)
     }
 
-    /// Refreshes the package list from the repository
-    pub fn refresh_packages(&mut self) -> Result<()> {
-        self.refresh_packages_with_selection(None)
+    /// Runs `brew update` (optionally) and reloads all packages on a background thread.
+    /// The result is applied by `poll_background_reload` once it arrives.
+    pub fn start_background_reload(&mut self, run_brew_update: bool) {
+        if self.background_load.is_some() {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let keg_sizes = self.repository.keg_sizes();
+        thread::spawn(move || {
+            if run_brew_update {
+                let _ = helpers::brew_update();
+            }
+            let packages = HomebrewRepository::fetch_installed_packages(&keg_sizes)
+                .map(|(packages, _)| packages);
+            let _ = tx.send(packages);
+        });
+        self.background_load = Some(rx);
+        self.reload_started = Instant::now();
+    }
+
+    /// Reloads the package list in the background, keeping the UI responsive
+    pub fn refresh_in_background(&mut self) {
+        if self.is_reloading() {
+            self.add_status_message("🔄 Already refreshing the package list".to_string());
+            return;
+        }
+        self.start_background_reload(self.run_brew_update);
+        self.add_status_message("🔄 Refreshing the package list...".to_string());
     }
 
-    /// Refreshes the package list from the repository, optionally preserving selection
-    fn refresh_packages_with_selection(&mut self, preserve_selection: Option<usize>) -> Result<()> {
-        // Use the new repository method to refresh all packages
-        self.repository.refresh_all_packages()?;
+    /// Starts a background reload once the configured refresh interval has passed since the
+    /// last one. Waits while an operation is running.
+    pub fn auto_refresh(&mut self) {
+        let Some(interval) = self.config.refresh_interval() else {
+            return;
+        };
+        if !self.is_reloading() && !self.is_updating && self.reload_started.elapsed() >= interval {
+            self.start_background_reload(self.run_brew_update);
+        }
+    }
 
-        // Get the refreshed packages from the repository
-        self.items = self.repository.get_all_packages()?;
-        self.apply_filter_with_selection(preserve_selection);
-        self.reset_column_scroll(); // Reset horizontal scrolling on refresh
+    /// Returns true if the package became outdated at the latest background refresh and
+    /// still is
+    pub fn is_newly_outdated(&self, package: &PackageInfo) -> bool {
+        self.newly_outdated.contains(&package.name) && package.has_update_available()
+    }
 
-        Ok(())
+    /// Returns true while a background reload is running
+    pub fn is_reloading(&self) -> bool {
+        self.background_load.is_some()
+    }
+
+    /// Applies the result of a background reload if it has arrived, keeping the selected package selected
+    pub fn poll_background_reload(&mut self) {
+        // Don't swap the package list from under a running operation
+        if self.is_updating {
+            return;
+        }
+
+        let Some(receiver) = &self.background_load else {
+            return;
+        };
+
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.background_load = None;
+                self.add_status_message(
+                    "⚠️  Failed to reload packages in the background".to_string(),
+                );
+                return;
+            }
+        };
+        self.background_load = None;
+
+        // Data loaded before an operation finished would bring back what it changed, so load
+        // again. The discarded reload already ran `brew update`.
+        if self
+            .operation_finished
+            .is_some_and(|finished| finished > self.reload_started)
+        {
+            self.start_background_reload(false);
+            return;
+        }
+
+        // Keep showing the current packages if Homebrew failed, rather than its error
+        let repository = match result {
+            Ok(packages) => {
+                HomebrewRepository::from_installed(packages, self.repository.keg_sizes())
+            }
+            Err(e) => {
+                self.add_status_message(format!("⚠️  Failed to reload packages: {}", e));
+                return;
+            }
+        };
+        let new_items = match repository.get_all_packages() {
+            Ok(items) => items,
+            Err(e) => {
+                self.add_status_message(format!("⚠️  Failed to reload packages: {}", e));
+                return;
+            }
+        };
+
+        let changes = PackageChanges::between(&self.items, &new_items);
+        let selected_name = self.get_selected_package().map(|pkg| pkg.name.clone());
+        let selected_index = self.list_state.selected();
+
+        self.repository = repository;
+        self.items = new_items;
+        self.apply_filter_with_selection(selected_index);
+        if let Some(name) = selected_name {
+            self.select_package_by_name(&name);
+        }
+        self.stale_since = None;
+        self.refreshed_at = helpers::unix_now();
+        self.newly_outdated = changes.newly_outdated.iter().cloned().collect();
+        let _ = package_cache::save(&self.items);
+
+        if changes.is_empty() {
+            self.add_status_message("📦 Package list is up to date".to_string());
+        } else if changes.newly_outdated.is_empty() {
+            self.add_status_message(format!(
+                "📦 Package list refreshed: {} added, {} removed, {} changed",
+                changes.added, changes.removed, changes.changed
+            ));
+        } else {
+            self.add_status_message(format!(
+                "📦 Package list refreshed: {} added, {} removed, {} changed, {} newly outdated",
+                changes.added,
+                changes.removed,
+                changes.changed,
+                changes.newly_outdated.len()
+            ));
+        }
+    }
+
+    /// Selects the package with the given name in the displayed list, returning false if it isn't shown
+    fn select_package_by_name(&mut self, name: &str) -> bool {
+        match self
+            .get_display_items()
+            .iter()
+            .position(|pkg| pkg.name == name)
+        {
+            Some(index) => {
+                self.list_state.select(Some(index));
+                self.pending_visibility_check = true;
+                true
+            }
+            None => false,
+        }
     }
 
     /// Moves to the next item in the list
     pub fn next(&mut self) {
-        let items_len = if self.is_searching {
-            self.filtered_items.len()
-        } else {
-            self.items.len()
-        };
+        let items_len = self.get_display_items().len();
 
         if items_len == 0 {
             return;
@@ -139,11 +397,7 @@ impl App {
 
     /// Moves to the previous item in the list
     pub fn previous(&mut self) {
-        let items_len = if self.is_searching {
-            self.filtered_items.len()
-        } else {
-            self.items.len()
-        };
+        let items_len = self.get_display_items().len();
 
         if items_len == 0 {
             return;
@@ -164,6 +418,27 @@ impl App {
         self.reset_scroll();
     }
 
+    /// Selects the item at the given index of the displayed list, if there is one
+    pub fn select_index(&mut self, index: usize) {
+        if index < self.get_display_items().len() {
+            self.list_state.select(Some(index));
+            self.ensure_selection_visible();
+            self.reset_scroll();
+        }
+    }
+
+    /// Moves the selection by the given number of items, stopping at both ends
+    pub fn scroll_selection(&mut self, delta: isize) {
+        let items_len = self.get_display_items().len();
+        if items_len == 0 {
+            return;
+        }
+
+        let current = self.list_state.selected().unwrap_or(0) as isize;
+        let new_index = (current + delta).clamp(0, items_len as isize - 1);
+        self.select_index(new_index as usize);
+    }
+
     /// Resets the scroll position and updates last interaction time
     pub fn reset_scroll(&mut self) {
         self.scroll_offset = 0;
@@ -203,11 +478,7 @@ impl App {
 
     /// Moves down by a page (10 items)
     pub fn page_down(&mut self) {
-        let items_len = if self.is_searching {
-            self.filtered_items.len()
-        } else {
-            self.items.len()
-        };
+        let items_len = self.get_display_items().len();
 
         if items_len == 0 {
             return;
@@ -223,11 +494,7 @@ impl App {
 
     /// Moves up by a page (10 items)
     pub fn page_up(&mut self) {
-        let items_len = if self.is_searching {
-            self.filtered_items.len()
-        } else {
-            self.items.len()
-        };
+        let items_len = self.get_display_items().len();
 
         if items_len == 0 {
             return;
@@ -243,11 +510,7 @@ impl App {
 
     /// Moves to the first item
     pub fn first(&mut self) {
-        let items_len = if self.is_searching {
-            self.filtered_items.len()
-        } else {
-            self.items.len()
-        };
+        let items_len = self.get_display_items().len();
 
         if items_len > 0 {
             self.list_state.select(Some(0));
@@ -258,11 +521,7 @@ impl App {
 
     /// Moves to the last item
     pub fn go_to_last(&mut self) {
-        let items_len = if self.is_searching {
-            self.filtered_items.len()
-        } else {
-            self.items.len()
-        };
+        let items_len = self.get_display_items().len();
 
         if items_len > 0 {
             self.list_state.select(Some(items_len - 1));
@@ -289,11 +548,7 @@ impl App {
             return; // No horizontal movement in single column
         }
 
-        let items_len = if self.is_searching {
-            self.filtered_items.len()
-        } else {
-            self.items.len()
-        };
+        let items_len = self.get_display_items().len();
 
         if items_len == 0 {
             return;
@@ -325,11 +580,7 @@ impl App {
             return; // No horizontal movement in single column
         }
 
-        let items_len = if self.is_searching {
-            self.filtered_items.len()
-        } else {
-            self.items.len()
-        };
+        let items_len = self.get_display_items().len();
 
         if items_len == 0 {
             return;
@@ -360,11 +611,7 @@ impl App {
 
     /// Updates the horizontal scroll offset for long package names
     pub fn update_scroll(&mut self, available_width: usize) {
-        let items = if self.is_searching {
-            &self.filtered_items
-        } else {
-            &self.items
-        };
+        let items = self.get_display_items();
 
         if let Some(selected) = self.list_state.selected()
             && selected < items.len()
@@ -384,11 +631,7 @@ impl App {
 
     /// Gets the currently selected package
     pub fn get_selected_package(&self) -> Option<&PackageInfo> {
-        let items = if self.is_searching {
-            &self.filtered_items
-        } else {
-            &self.items
-        };
+        let items = self.get_display_items();
 
         self.list_state.selected().and_then(|i| items.get(i))
     }
@@ -400,19 +643,25 @@ impl App {
 
     /// Gets the current list of packages to display
     pub fn get_display_items(&self) -> &Vec<PackageInfo> {
-        if self.is_searching {
+        if self.is_filtering() {
             &self.filtered_items
         } else {
             &self.items
         }
     }
 
+    /// Returns true if the displayed list is narrowed by a search or quick filters
+    pub fn is_filtering(&self) -> bool {
+        self.is_searching || self.filters.is_active()
+    }
+
     /// Starts search mode
     pub fn start_search(&mut self) {
         // Save current selection before starting search
         self.pre_search_selection = self.list_state.selected();
         self.is_searching = true;
-        self.search_query.clear();
+        self.search.clear();
+        self.history_position = None;
         self.reset_column_scroll(); // Reset horizontal scrolling when starting search
         self.apply_filter();
     }
@@ -423,46 +672,665 @@ impl App {
         let selected_package_name = self.get_selected_package().map(|pkg| pkg.name.clone());
 
         self.is_searching = false;
-        self.search_query.clear();
+        self.search.clear();
+        self.apply_filter();
 
-        // Find and select the same package in the full items list
+        // Find and select the same package in the list that is now displayed
         if let Some(package_name) = selected_package_name {
-            // Find the index of this package in the full items list
-            if let Some(index) = self.items.iter().position(|pkg| pkg.name == package_name) {
-                self.list_state.select(Some(index));
-                // Mark that we need to ensure visibility on next layout update
-                self.pending_visibility_check = true;
-            } else if !self.items.is_empty() {
-                // Fallback to first item if package not found (shouldn't happen)
-                self.list_state.select(Some(0));
-                self.pending_visibility_check = true;
-            } else {
-                self.list_state.select(None);
+            self.select_package_by_name(&package_name);
+        }
+
+        // Mark that we need to ensure visibility on next layout update
+        self.pending_visibility_check = true;
+        self.reset_scroll();
+    }
+
+    /// Starts search mode with the given query already typed in
+    pub fn start_search_with_query(&mut self, query: &str) {
+        self.start_search();
+        self.search.set_text(query);
+        self.apply_filter();
+    }
+
+    /// Replaces the quick filters and re-applies them to the package list
+    pub fn set_filters(&mut self, filters: QuickFilters) {
+        let selected_name = self.get_selected_package().map(|pkg| pkg.name.clone());
+        self.filters = filters;
+        self.apply_filter();
+        if let Some(name) = selected_name {
+            self.select_package_by_name(&name);
+        }
+        self.reset_column_scroll();
+    }
+
+    /// Switches to the next sort mode, keeping the selected package selected,
+    /// and remembers it for the next session
+    pub fn cycle_sort_mode(&mut self) {
+        let sizes_known = self.items.iter().any(|pkg| pkg.installed_size.is_some());
+        self.set_sort_mode(self.sort_mode.next(sizes_known));
+    }
+
+    /// Sorts the list in the given order, keeping the selected package selected,
+    /// and remembers it for the next session
+    pub fn set_sort_mode(&mut self, sort_mode: SortMode) {
+        let selected_name = self.get_selected_package().map(|pkg| pkg.name.clone());
+
+        self.sort_mode = sort_mode;
+        self.apply_filter();
+        if let Some(name) = selected_name {
+            self.select_package_by_name(&name);
+        }
+
+        let state = UiState {
+            sort_mode: self.sort_mode,
+        };
+        if let Err(e) = state.save() {
+            self.add_status_message(format!("⚠️  Failed to remember the sort order: {}", e));
+        }
+    }
+
+    /// Changes the quick filters, keeping the selected package selected if it still shows
+    pub fn update_filters(&mut self, change: impl FnOnce(&mut QuickFilters)) {
+        let mut filters = self.filters.clone();
+        change(&mut filters);
+        self.set_filters(filters);
+    }
+
+    /// Narrows the list to the next tap of the installed packages, then back to every tap
+    pub fn cycle_tap_filter(&mut self) {
+        let taps = self.installed_taps();
+        self.update_filters(|filters| filters.cycle_tap(&taps));
+    }
+
+    /// Gets the taps of the installed packages, sorted
+    fn installed_taps(&self) -> Vec<String> {
+        let mut taps: Vec<String> = self
+            .items
+            .iter()
+            .filter_map(|pkg| pkg.tap_name().map(str::to_string))
+            .collect();
+        taps.sort();
+        taps.dedup();
+        taps
+    }
+
+    /// Narrows the list to the next of the user's collections, then back to every package
+    pub fn cycle_collection_filter(&mut self) {
+        let collections = self.collections.names();
+        if collections.is_empty() {
+            self.add_status_message(format!(
+                "No collections yet. Press {} to add the selected package to one",
+                self.keymap
+                    .first_key(KeyMode::Normal, Action::EditCollections)
+            ));
+            return;
+        }
+        self.update_filters(|filters| filters.cycle_collection(&collections));
+    }
+
+    /// Shows the collections to add the selected package to, or remove it from
+    pub fn show_collections(&mut self) {
+        let Some(package) = self.get_selected_package().map(|pkg| pkg.name.clone()) else {
+            return;
+        };
+        let items = self
+            .collections
+            .names()
+            .into_iter()
+            .map(|name| {
+                let detail = if self.collections.contains(&name, &package) {
+                    format!("✓ has {}", package)
+                } else {
+                    match self.collections.members(&name).len() {
+                        1 => "1 package".to_string(),
+                        count => format!("{} packages", count),
+                    }
+                };
+                PickerItem {
+                    label: name,
+                    detail,
+                    action: None,
+                }
+            })
+            .collect();
+        self.modal_state = ModalState::Picker(Picker::new(
+            PickerKind::Collection(package.clone()),
+            &format!("Collections of {} (type a new name to create one)", package),
+            items,
+        ));
+    }
+
+    /// Updates every outdated package of the collection being browsed, one after another
+    pub fn update_collection(&mut self) {
+        let Some(collection) = self.browsed_collection() else {
+            return;
+        };
+        let members: Vec<String> = self
+            .items
+            .iter()
+            .filter(|pkg| pkg.collections.contains(&collection))
+            .map(|pkg| pkg.name.clone())
+            .collect();
+        if !self.update_packages(&members) {
+            self.add_status_message(format!("Every package in {} is up to date", collection));
+        }
+    }
+
+    /// Updates the outdated, unpinned packages among the named ones, one after another.
+    /// Returns false if none of them needed updating.
+    fn update_packages(&mut self, names: &[String]) -> bool {
+        let mut outdated: VecDeque<String> = self
+            .items
+            .iter()
+            .filter(|pkg| names.contains(&pkg.name))
+            .filter(|pkg| pkg.has_update_available() && !pkg.pinned)
+            .map(|pkg| pkg.name.clone())
+            .collect();
+        let Some(first) = outdated.pop_front() else {
+            return false;
+        };
+        self.operation_queue = outdated;
+        self.start_mock_update(first);
+        true
+    }
+
+    /// Asks to uninstall every installed package of the collection being browsed
+    pub fn uninstall_collection(&mut self) {
+        let Some(collection) = self.browsed_collection() else {
+            return;
+        };
+        self.pending_uninstall = self
+            .items
+            .iter()
+            .filter(|pkg| pkg.collections.contains(&collection))
+            .map(|pkg| pkg.name.clone())
+            .collect();
+        if self.pending_uninstall.is_empty() {
+            self.add_status_message(format!("No installed packages in {}", collection));
+        } else {
+            self.modal_state = ModalState::UninstallConfirmation;
+        }
+    }
+
+    /// Gets the collection the list is narrowed to for a batch operation, explaining how to pick
+    /// one if there is none or another operation is running
+    fn browsed_collection(&mut self) -> Option<String> {
+        if self.is_updating {
+            self.add_status_message("Another operation is currently in progress".to_string());
+            return None;
+        }
+        let collection = self.filters.collection.clone();
+        if collection.is_none() {
+            self.add_status_message(format!(
+                "Press {} to browse a collection first",
+                self.keymap
+                    .first_key(KeyMode::Normal, Action::FilterCollection)
+            ));
+        }
+        collection
+    }
+
+    /// Opens the `:` command line
+    pub fn open_command_line(&mut self) {
+        self.command_line = Some(LineEditor::default());
+        self.history_position = None;
+    }
+
+    /// Closes the `:` command line without running it
+    pub fn close_command_line(&mut self) {
+        self.command_line = None;
+        self.completions.clear();
+        self.history_position = None;
+    }
+
+    /// Completes the word before the cursor on the command line. Several candidates are
+    /// completed as far as they agree, and listed if they agree no further.
+    pub fn complete_command(&mut self) {
+        let Some(command_line) = &self.command_line else {
+            return;
+        };
+        let source = CompletionSource {
+            packages: self.items.iter().map(|pkg| pkg.name.as_str()).collect(),
+            taps: self.installed_taps(),
+            collections: self.collections.names(),
+        };
+        let line = command_line.text_before_cursor();
+        let candidates = command_line::completions(&line, &source);
+        let word = line.rsplit(char::is_whitespace).next().unwrap_or_default();
+
+        let completion = match candidates.as_slice() {
+            [] => return,
+            [candidate] => format!("{} ", candidate),
+            _ => {
+                let prefix = command_line::common_prefix(&candidates);
+                if prefix.len() > word.len() {
+                    prefix
+                } else {
+                    self.completions = candidates;
+                    return;
+                }
             }
-        } else if !self.items.is_empty() {
-            // No selection in search mode, select first item
-            self.list_state.select(Some(0));
-            self.pending_visibility_check = true;
+        };
+        self.edit_input(|input| input.replace_word_before(&completion));
+    }
+
+    /// Runs the command typed on the command line and remembers it in the command history
+    pub fn submit_command_line(&mut self) {
+        let Some(input) = self
+            .command_line
+            .take()
+            .map(|command_line| command_line.text())
+        else {
+            return;
+        };
+        self.history_position = None;
+        if input.trim().is_empty() {
+            return;
+        }
+        self.search_store.record_command(&input);
+        self.save_search_store();
+
+        match Command::parse(&input) {
+            Ok(command) => self.run_command(command),
+            Err(e) => self.add_status_message(format!("⚠️  {}", e)),
+        }
+    }
+
+    /// Runs a command from the command line
+    fn run_command(&mut self, command: Command) {
+        let result = match command {
+            Command::Upgrade(names) if names.is_empty() => self.update_selected_package(),
+            Command::Uninstall(names) if names.is_empty() => self.uninstall_selected_package(),
+            Command::Upgrade(names) => {
+                if self.check_packages_command(&names) && !self.update_packages(&names) {
+                    let verb = if names.len() == 1 { "is" } else { "are" };
+                    self.add_status_message(format!(
+                        "{} {} up to date or pinned",
+                        names.join(", "),
+                        verb
+                    ));
+                }
+                Ok(())
+            }
+            Command::Uninstall(names) => {
+                if self.check_packages_command(&names) {
+                    self.pending_uninstall = names;
+                    self.modal_state = ModalState::UninstallConfirmation;
+                }
+                Ok(())
+            }
+            Command::Sort(sort_mode) => {
+                self.set_sort_mode(sort_mode);
+                Ok(())
+            }
+            Command::Filter(change) => {
+                self.update_filters(|filters| change.apply(filters));
+                Ok(())
+            }
+            Command::Export(format, path) => {
+                self.export_packages(format, &path);
+                Ok(())
+            }
+            Command::Search(query) => {
+                self.start_search_with_query(&query);
+                Ok(())
+            }
+            Command::Quit => {
+                self.quit();
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            self.add_status_message(format!("❌ {}", e));
+        }
+    }
+
+    /// Checks that a command naming packages can run: every package is installed and no other
+    /// operation is running. Explains why not in the status bar otherwise.
+    fn check_packages_command(&mut self, names: &[String]) -> bool {
+        if self.is_updating {
+            self.add_status_message("Another operation is currently in progress".to_string());
+            return false;
+        }
+        let unknown: Vec<&str> = names
+            .iter()
+            .filter(|name| !self.items.iter().any(|pkg| &pkg.name == *name))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            self.add_status_message(format!("⚠️  Not installed: {}", unknown.join(", ")));
+            return false;
+        }
+        true
+    }
+
+    /// Edits the text input that has focus: the open prompt, the picker's filter, the command
+    /// line or the search prompt, re-applying the search if the query changed
+    pub fn edit_input(&mut self, edit: impl FnOnce(&mut LineEditor)) {
+        match &mut self.modal_state {
+            ModalState::Prompt(prompt) => edit(&mut prompt.input),
+            ModalState::Picker(picker) => picker.edit_filter(edit),
+            _ if let Some(command_line) = &mut self.command_line => {
+                edit(command_line);
+                self.completions.clear();
+            }
+            _ if self.is_searching => {
+                let previous = self.search.text();
+                edit(&mut self.search);
+                if self.search.text() != previous {
+                    self.apply_filter();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Ends the search, remembering the query in the search history
+    pub fn submit_search(&mut self) {
+        self.search_store.record(&self.search.text());
+        self.save_search_store();
+        self.end_search();
+    }
+
+    /// Replaces the search query with an older or newer query from the history.
+    /// Going newer than the newest query brings back what was typed before.
+    pub fn recall_history(&mut self, older: bool) {
+        let history = if self.command_line.is_some() {
+            &self.search_store.commands
         } else {
-            self.list_state.select(None);
+            &self.search_store.history
+        };
+        let len = history.len();
+        let position = match (self.history_position, older) {
+            (None, true) if len > 0 => Some(len - 1),
+            (Some(position), true) => Some(position.saturating_sub(1)),
+            (Some(position), false) if position + 1 < len => Some(position + 1),
+            (Some(_), false) => None,
+            (None, _) => return,
+        };
+
+        let text = match position {
+            Some(position) => history[position].clone(),
+            None => self.history_draft.clone(),
+        };
+        if self.history_position.is_none() {
+            self.history_draft = match &self.command_line {
+                Some(command_line) => command_line.text(),
+                None => self.search.text(),
+            };
         }
+        self.history_position = position;
+        self.edit_input(|input| input.set_text(&text));
+    }
 
-        self.reset_scroll();
+    /// Asks for a name to save the current search and filters under
+    pub fn prompt_search_name(&mut self) {
+        self.modal_state =
+            ModalState::Prompt(Prompt::new(PromptKind::SearchName, "Save Search As", ""));
     }
 
-    /// Adds a character to the search query
-    pub fn add_search_char(&mut self, c: char) {
-        if self.is_searching {
-            self.search_query.push(c);
-            self.apply_filter();
+    /// Uses the text typed into the open prompt
+    pub fn submit_prompt(&mut self) {
+        let ModalState::Prompt(prompt) = &self.modal_state else {
+            return;
+        };
+        let (kind, text) = (prompt.kind.clone(), prompt.input.text().trim().to_string());
+        match kind {
+            PromptKind::SearchName => {
+                if text.is_empty() {
+                    self.add_status_message("A saved search needs a name".to_string());
+                    return;
+                }
+                self.search_store.save_search(SavedSearch {
+                    name: text.clone(),
+                    query: self.search.text().trim().to_string(),
+                    filters: self.filters.clone(),
+                });
+                self.save_search_store();
+                self.add_status_message(format!("💾 Saved search \"{}\"", text));
+            }
+            PromptKind::Note(name) => {
+                self.notes.set(&name, &text);
+                match self.notes.save() {
+                    Err(e) => {
+                        self.add_status_message(format!("⚠️  Failed to save notes: {}", e));
+                    }
+                    Ok(()) if text.is_empty() => {
+                        self.add_status_message(format!("Removed the note on {}", name));
+                    }
+                    Ok(()) => self.add_status_message(format!("📝 Saved the note on {}", name)),
+                }
+                // The note may change which packages the search matches
+                self.apply_filter_with_selection(self.list_state.selected());
+                self.select_package_by_name(&name);
+            }
+            PromptKind::ExportPath => {
+                if text.is_empty() {
+                    self.add_status_message("An export needs a file name".to_string());
+                    return;
+                }
+                let format = ExportFormat::from_path(&helpers::expand_home(&text));
+                self.export_packages(format, &text);
+            }
         }
+        self.modal_state = ModalState::None;
+    }
+
+    /// Asks for the file to export the shown packages to
+    pub fn prompt_export_path(&mut self) {
+        self.modal_state = ModalState::Prompt(Prompt::new(
+            PromptKind::ExportPath,
+            "Export To (.json, .csv, .md or a Brewfile)",
+            "~/Brewfile",
+        ));
     }
 
-    /// Removes the last character from the search query
-    pub fn remove_search_char(&mut self) {
-        if self.is_searching && !self.search_query.is_empty() {
-            self.search_query.pop();
-            self.apply_filter();
+    /// Writes the shown packages to a file in the given format
+    pub fn export_packages(&mut self, format: ExportFormat, path: &str) {
+        let packages = self.get_display_items();
+        let written = export::format_packages(packages, format)
+            .and_then(|output| Ok(std::fs::write(helpers::expand_home(path), output)?));
+        match written {
+            Ok(()) => self.add_status_message(format!(
+                "📤 Exported {} packages to {}",
+                self.get_display_items().len(),
+                path
+            )),
+            Err(e) => self.add_status_message(format!("⚠️  Failed to export to {}: {}", path, e)),
+        }
+    }
+
+    /// Opens the homepage of the selected package in the browser
+    pub fn open_selected_homepage(&mut self) {
+        let Some(package) = self.get_selected_package() else {
+            return;
+        };
+        match &package.homepage {
+            None => {
+                let message = format!("{} has no homepage", package.name);
+                self.add_status_message(message);
+            }
+            Some(homepage) => {
+                if let Err(e) = helpers::open_url(homepage) {
+                    self.add_status_message(format!("⚠️  {}", e));
+                }
+            }
+        }
+    }
+
+    /// Pins the selected formula so upgrades leave it alone, or unpins it
+    pub fn toggle_selected_pin(&mut self) {
+        let Some(package) = self.get_selected_package() else {
+            return;
+        };
+        let (name, pin) = (package.name.clone(), !package.pinned);
+        if package.package_type != PackageType::Formulae {
+            self.add_status_message(format!("{} is a cask; only formulae can be pinned", name));
+            return;
+        }
+
+        if let Err(e) = self.repository.pin_package(&name, pin) {
+            self.add_status_message(format!("❌ {}", e));
+            return;
+        }
+        for package in self.items.iter_mut().filter(|pkg| pkg.name == name) {
+            package.pinned = pin;
+        }
+        // The pinned filter may no longer show the package
+        self.apply_filter_with_selection(self.list_state.selected());
+        self.select_package_by_name(&name);
+        self.add_status_message(if pin {
+            format!("📌 Pinned {}", name)
+        } else {
+            format!("Unpinned {}", name)
+        });
+    }
+
+    /// Shows every action of the package list, to search for one and run it
+    pub fn show_command_palette(&mut self) {
+        let items = Action::ALL
+            .into_iter()
+            .filter(|&action| action != Action::CommandPalette && KeyMode::Normal.supports(action))
+            .map(|action| PickerItem {
+                label: action.description().to_string(),
+                detail: self.keymap.keys_label(KeyMode::Normal, action),
+                action: Some(action),
+            })
+            .collect();
+        self.modal_state = ModalState::Picker(Picker::new(PickerKind::Command, "Commands", items));
+    }
+
+    /// Closes the command palette and returns to the package list, returning the action chosen
+    /// to run there. Returns nothing if another picker is open or nothing is selected.
+    pub fn take_picked_command(&mut self) -> Option<Action> {
+        let ModalState::Picker(picker) = &self.modal_state else {
+            return None;
+        };
+        if picker.kind != PickerKind::Command {
+            return None;
+        }
+        let action = picker.selected_item()?.action?;
+        self.modal_state = ModalState::None;
+        self.screen = Screen::Packages;
+        Some(action)
+    }
+
+    /// Asks for the note of the selected package, starting from its current note
+    pub fn prompt_package_note(&mut self) {
+        let Some(name) = self.get_selected_package().map(|pkg| pkg.name.clone()) else {
+            return;
+        };
+        let note = self.notes.get(&name).unwrap_or_default().to_string();
+        self.modal_state = ModalState::Prompt(Prompt::new(
+            PromptKind::Note(name.clone()),
+            &format!("Note on {}", name),
+            &note,
+        ));
+    }
+
+    /// Shows the saved searches to pick one to bring back
+    pub fn show_saved_searches(&mut self) {
+        if self.search_store.saved.is_empty() {
+            self.add_status_message(format!(
+                "No saved searches yet. Press {} while searching to save one",
+                self.keymap.first_key(KeyMode::Search, Action::SaveSearch)
+            ));
+            return;
+        }
+        let items = self
+            .search_store
+            .saved
+            .iter()
+            .map(|saved| PickerItem {
+                label: saved.name.clone(),
+                detail: saved.summary(),
+                action: None,
+            })
+            .collect();
+        self.modal_state = ModalState::Picker(Picker::new(
+            PickerKind::SavedSearch,
+            "Saved Searches",
+            items,
+        ));
+    }
+
+    /// Uses the item selected in the open picker
+    pub fn choose_picked(&mut self) {
+        let ModalState::Picker(picker) = &self.modal_state else {
+            return;
+        };
+        let kind = picker.kind.clone();
+        // A name that isn't exactly an existing collection creates a new one
+        let Some(label) = picker.selected_label() else {
+            return;
+        };
+        match kind {
+            PickerKind::SavedSearch => {
+                let Some(saved) = self.search_store.find_saved(&label).cloned() else {
+                    return;
+                };
+                self.modal_state = ModalState::None;
+                self.set_filters(saved.filters);
+                self.start_search_with_query(&saved.query);
+            }
+            PickerKind::Collection(package) => {
+                self.modal_state = ModalState::None;
+                let added = self.collections.toggle(&label, &package);
+                if let Err(e) = self.collections.save() {
+                    self.add_status_message(format!("⚠️  Failed to save collections: {}", e));
+                } else if added {
+                    self.add_status_message(format!("🏷️  Added {} to {}", package, label));
+                } else {
+                    self.add_status_message(format!("Removed {} from {}", package, label));
+                }
+                // The collection filter may no longer show the package
+                self.apply_filter_with_selection(self.list_state.selected());
+                self.select_package_by_name(&package);
+            }
+            // Commands are run by the event handler, through take_picked_command
+            PickerKind::Command => {}
+        }
+    }
+
+    /// Removes the item selected in the open picker
+    pub fn remove_picked(&mut self) {
+        let ModalState::Picker(picker) = &mut self.modal_state else {
+            return;
+        };
+        if picker.kind != PickerKind::SavedSearch {
+            return;
+        }
+        let Some(item) = picker.remove_selected() else {
+            return;
+        };
+        self.search_store.remove_saved(&item.label);
+        self.save_search_store();
+        self.add_status_message(format!("Removed saved search \"{}\"", item.label));
+    }
+
+    /// Selects a row of the open picker by its position
+    pub fn select_picker_row(&mut self, row: usize) {
+        if let ModalState::Picker(picker) = &mut self.modal_state {
+            picker.select(row);
+        }
+    }
+
+    /// Moves the selection of the open picker
+    pub fn move_picker_selection(&mut self, delta: isize) {
+        if let ModalState::Picker(picker) = &mut self.modal_state {
+            picker.move_selection(delta);
+        }
+    }
+
+    /// Closes the open prompt or picker without using it
+    pub fn cancel_modal(&mut self) {
+        self.modal_state = ModalState::None;
+    }
+
+    /// Persists the search history and saved searches, reporting failures in the status bar
+    fn save_search_store(&mut self) {
+        if let Err(e) = self.search_store.save() {
+            self.add_status_message(format!("⚠️  Failed to save searches: {}", e));
         }
     }
 
@@ -472,29 +1340,33 @@ impl App {
     }
 
     fn apply_filter_with_selection(&mut self, preserve_selection: Option<usize>) {
-        if self.search_query.is_empty() {
-            self.filtered_items = self.items.clone();
-        } else {
-            let query_lower = self.search_query.to_lowercase();
-            self.filtered_items = self
-                .items
-                .iter()
-                .filter(|pkg| {
-                    pkg.name.to_lowercase().contains(&query_lower)
-                        || pkg.description.to_lowercase().contains(&query_lower)
-                })
-                .cloned()
-                .collect();
+        self.notes.annotate(&mut self.items);
+        self.collections.annotate(&mut self.items);
+        self.sort_mode.sort(&mut self.items);
+        // An invalid query is reported and leaves only the quick filters applied
+        match Query::parse(&self.search.text()) {
+            Ok(query) => {
+                self.query = query;
+                self.query_error = None;
+            }
+            Err(e) => {
+                self.query = Query::default();
+                self.query_error = Some(e.to_string());
+            }
         }
+        let filtered = self
+            .items
+            .iter()
+            .filter(|pkg| self.filters.matches(pkg) && self.query.matches(pkg));
+        self.filtered_items = fuzzy::rank(filtered, &self.query.text)
+            .into_iter()
+            .cloned()
+            .collect();
 
         // Apply selection based on preservation request
         if let Some(target_index) = preserve_selection {
             // Preserve selection at the given index
-            let max_index = if self.is_searching {
-                self.filtered_items.len()
-            } else {
-                self.items.len()
-            };
+            let max_index = self.get_display_items().len();
 
             if max_index > 0 {
                 // Ensure index is within bounds
@@ -504,8 +1376,8 @@ impl App {
                 self.list_state.select(None);
             }
         } else {
-            // Default behavior: Reset selection to first item after filtering
-            if !self.filtered_items.is_empty() {
+            // Default behaviour: Reset selection to first item after filtering
+            if !self.get_display_items().is_empty() {
                 self.list_state.select(Some(0));
             } else {
                 self.list_state.select(None);
@@ -519,7 +1391,7 @@ impl App {
         if let Some(package) = self.get_selected_package() {
             if !self.is_updating {
                 // Show confirmation modal instead of immediately uninstalling
-                self.pending_uninstall_package = Some(package.name.clone());
+                self.pending_uninstall = vec![package.name.clone()];
                 self.modal_state = ModalState::UninstallConfirmation;
             } else {
                 self.add_status_message("Another operation is currently in progress".to_string());
@@ -584,7 +1456,7 @@ impl App {
             .map(|start| start.elapsed())
             .unwrap_or_default();
 
-        let package_name = self.update_package_name.as_ref().unwrap();
+        let package_name = self.update_package_name.clone().unwrap();
 
         match self.update_stage {
             UpdateStage::Starting if elapsed > Duration::from_millis(800) => {
@@ -598,10 +1470,28 @@ impl App {
             UpdateStage::Installing if elapsed > Duration::from_millis(4000) => {
                 // Call real update during Installing stage if not called yet
                 if !self.real_update_called && !self.is_uninstalling {
-                    if let Err(e) = self.repository.update_package(package_name) {
+                    let (kind, output) = match &self.reinstalling {
+                        Some(removed) => (
+                            OperationKind::Install,
+                            self.repository.install_package(
+                                &removed.name,
+                                &removed.package_type,
+                                removed.tap.as_deref(),
+                                &removed.options,
+                            ),
+                        ),
+                        None => (
+                            OperationKind::Upgrade,
+                            self.repository.update_package(&package_name),
+                        ),
+                    };
+                    self.log_operation(kind, &package_name, &output, None);
+                    if !output.success() {
                         self.add_status_message(format!(
-                            "❌ Failed to update {}: {}",
-                            package_name, e
+                            "❌ Failed to {} {}: {}",
+                            self.update_verb(),
+                            package_name,
+                            output.last_line()
                         ));
                         self.finish_mock_update();
                         return;
@@ -614,7 +1504,11 @@ impl App {
             }
             UpdateStage::Completing if elapsed > Duration::from_millis(5000) => {
                 self.update_stage = UpdateStage::Finished;
-                self.add_status_message(format!("✅ {} updated successfully!", package_name));
+                self.add_status_message(format!(
+                    "✅ {} {}d successfully!",
+                    package_name,
+                    self.update_verb()
+                ));
             }
             UpdateStage::Finished if elapsed > Duration::from_millis(6000) => {
                 // Reset update state
@@ -628,10 +1522,22 @@ impl App {
             UpdateStage::UninstallRemoving if elapsed > Duration::from_millis(2000) => {
                 // Call real uninstall during UninstallRemoving stage if not called yet
                 if !self.real_update_called && self.is_uninstalling {
-                    if let Err(e) = self.repository.uninstall_package(package_name) {
+                    let removed = self
+                        .items
+                        .iter()
+                        .find(|pkg| pkg.name == package_name)
+                        .map(RemovedPackage::from_package);
+                    let output = self.repository.uninstall_package(&package_name);
+                    let removed = removed.filter(|_| output.success());
+                    if let Some(removed) = &removed {
+                        self.last_removal = Some((removed.clone(), Instant::now()));
+                    }
+                    self.log_operation(OperationKind::Uninstall, &package_name, &output, removed);
+                    if !output.success() {
                         self.add_status_message(format!(
                             "❌ Failed to uninstall {}: {}",
-                            package_name, e
+                            package_name,
+                            output.last_line()
                         ));
                         self.finish_mock_uninstall();
                         return;
@@ -654,13 +1560,51 @@ impl App {
         }
     }
 
+    /// Writes an operation on a package to the operation log, with the versions it went from
+    /// and to as far as they are known
+    fn log_operation(
+        &mut self,
+        kind: OperationKind,
+        package_name: &str,
+        output: &BrewOutput,
+        removed: Option<RemovedPackage>,
+    ) {
+        let package = self.items.iter().find(|pkg| pkg.name == package_name);
+        let mut record =
+            OperationRecord::now(kind, package_name, output.exit_code, output.output.clone());
+        record.from_version = package.and_then(|pkg| pkg.installed_version.clone());
+        match kind {
+            OperationKind::Upgrade if output.success() => {
+                record.to_version = package.map(|pkg| pkg.current_version.clone());
+            }
+            OperationKind::Install if output.success() => {
+                record.to_version = self
+                    .repository
+                    .refresh_package(package_name)
+                    .ok()
+                    .flatten()
+                    .and_then(|pkg| pkg.installed_version);
+            }
+            _ => {}
+        }
+        record.removed = removed;
+        if let Err(e) = operation_log::append(&record) {
+            self.add_status_message(format!("⚠️  Failed to write the operation log: {}", e));
+        }
+    }
+
     /// Finishes the mock uninstall and removes package from list
     fn finish_mock_uninstall(&mut self) {
-        let package_name = self.update_package_name.clone();
+        // A failed uninstall leaves the package installed
+        let package_name = self
+            .update_package_name
+            .clone()
+            .filter(|_| self.real_update_called);
 
         // Save current selection before making changes
         let current_selection = self.list_state.selected();
 
+        self.operation_finished = Some(Instant::now());
         self.is_updating = false;
         self.is_uninstalling = false;
         self.real_update_called = false;
@@ -676,7 +1620,7 @@ impl App {
 
             // Remove from our package lists immediately since uninstall was successful
             self.items.retain(|p| p.name != name);
-            if self.is_searching {
+            if self.is_filtering() {
                 self.filtered_items.retain(|p| p.name != name);
             }
 
@@ -693,23 +1637,44 @@ impl App {
                 None
             };
 
-            // Refresh the entire package list to ensure consistency
-            // and apply the new selection
-            if let Err(e) = self.refresh_packages_with_selection(new_selection) {
-                self.add_status_message(format!("⚠️  Failed to refresh package list: {}", e));
-            }
+            self.apply_filter_with_selection(new_selection);
+
+            let undo_hint = match &self.last_removal {
+                Some((removed, _)) if removed.name == name => format!(
+                    ". Press {} to undo",
+                    self.keymap.first_key(KeyMode::Normal, Action::Undo)
+                ),
+                _ => String::new(),
+            };
+            self.add_status_message(format!("✅ Successfully uninstalled {}{}", name, undo_hint));
+        }
 
-            self.add_status_message(format!("✅ Successfully uninstalled {}", name));
+        match self.operation_queue.pop_front() {
+            Some(next) => self.start_mock_uninstall(next),
+            // Reload once the queue drains, for dependencies the batch changed
+            None => self.start_background_reload(false),
         }
     }
 
     /// Finishes the mock update and resets state
     fn finish_mock_update(&mut self) {
         let package_name = self.update_package_name.clone();
+        let reinstalled = self.reinstalling.take();
+        // Only a successful reinstall uses up the quick undo
+        if self.real_update_called
+            && let Some(removed) = &reinstalled
+            && self
+                .last_removal
+                .as_ref()
+                .is_some_and(|(last, _)| last.name == removed.name)
+        {
+            self.last_removal = None;
+        }
 
         // Save current selection before refreshing
         let current_selection = self.list_state.selected();
 
+        self.operation_finished = Some(Instant::now());
         self.is_updating = false;
         self.is_uninstalling = false;
         self.real_update_called = false;
@@ -728,12 +1693,70 @@ impl App {
                 self.add_status_message(format!("⚠️  Failed to refresh {}: {}", name, e));
             }
 
-            // Also refresh the entire package list to ensure consistency,
-            // preserving the cursor position on the updated package
-            if let Err(e) = self.refresh_packages_with_selection(current_selection) {
-                self.add_status_message(format!("⚠️  Failed to refresh package list: {}", e));
+            self.apply_filter_with_selection(current_selection);
+            if reinstalled.is_some() {
+                self.select_package_by_name(&name);
             }
         }
+
+        match self.operation_queue.pop_front() {
+            Some(next) => self.start_mock_update(next),
+            // Reload once the queue drains, for dependencies the batch changed
+            None => self.start_background_reload(false),
+        }
+    }
+
+    /// Gets the verb for the running update, which may be an undo reinstalling a package
+    pub fn update_verb(&self) -> &'static str {
+        if self.reinstalling.is_some() {
+            "reinstall"
+        } else {
+            "update"
+        }
+    }
+
+    /// Undoes the latest uninstall by installing the package again, if it was recent enough
+    pub fn undo_last_uninstall(&mut self) {
+        // The removal stays undoable until the reinstall succeeds
+        match &self.last_removal {
+            Some((removed, at)) if at.elapsed() < UNDO_WINDOW => self.reinstall(removed.clone()),
+            _ => self.add_status_message(format!(
+                "Nothing to undo. Older uninstalls can be undone from the history ({})",
+                self.keymap.first_key(KeyMode::Normal, Action::History)
+            )),
+        }
+    }
+
+    /// Undoes the uninstall selected on the history screen by installing the package again
+    pub fn undo_selected_uninstall(&mut self) {
+        let Some(record) = self.selected_operation() else {
+            return;
+        };
+        let Some(removed) = record.undoable_removal().cloned() else {
+            let message = match record.kind {
+                OperationKind::Uninstall => format!("{} was not uninstalled", record.package),
+                _ => "Only uninstalls can be undone".to_string(),
+            };
+            self.add_status_message(message);
+            return;
+        };
+        if self.items.iter().any(|pkg| pkg.name == removed.name) {
+            self.add_status_message(format!("{} is installed again already", removed.name));
+            return;
+        }
+        self.screen = Screen::Packages;
+        self.reinstall(removed);
+    }
+
+    /// Installs a removed package again, showing the progress like an update
+    fn reinstall(&mut self, removed: RemovedPackage) {
+        if self.is_updating {
+            self.add_status_message("Another operation is currently in progress".to_string());
+            return;
+        }
+        let name = removed.name.clone();
+        self.reinstalling = Some(removed);
+        self.start_mock_update(name);
     }
 
     /// Gets the current update status message for display
@@ -746,7 +1769,11 @@ impl App {
         let elapsed = self.update_start_time?.elapsed();
 
         match self.update_stage {
-            UpdateStage::Starting => Some(format!("🔄 Preparing to update {}...", package_name)),
+            UpdateStage::Starting => Some(format!(
+                "🔄 Preparing to {} {}...",
+                self.update_verb(),
+                package_name
+            )),
             UpdateStage::Downloading => {
                 let dots = ".".repeat(((elapsed.as_millis() / 300) % 4) as usize);
                 Some(format!("⬇️  Downloading {} updates{}", package_name, dots))
@@ -758,7 +1785,11 @@ impl App {
             UpdateStage::Completing => {
                 Some(format!("✨ Finalizing {} installation...", package_name))
             }
-            UpdateStage::Finished => Some(format!("✅ {} updated successfully!", package_name)),
+            UpdateStage::Finished => Some(format!(
+                "✅ {} {}d successfully!",
+                package_name,
+                self.update_verb()
+            )),
             // Uninstall status messages
             UpdateStage::UninstallStarting => {
                 Some(format!("🗑️  Preparing to uninstall {}...", package_name))
@@ -781,6 +1812,182 @@ impl App {
         }
     }
 
+    /// Opens the install timeline screen, or returns to the package list if it is already open
+    pub fn toggle_timeline(&mut self) {
+        if self.screen == Screen::Timeline {
+            self.screen = Screen::Packages;
+            return;
+        }
+
+        self.timeline = TimelineEntry::collect(&self.items);
+        self.timeline_state.select(if self.timeline.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+        self.screen = Screen::Timeline;
+    }
+
+    /// Moves the timeline selection by the given number of entries, clamping at both ends
+    pub fn move_timeline_selection(&mut self, delta: isize) {
+        if self.timeline.is_empty() {
+            return;
+        }
+
+        let current = self.timeline_state.selected().unwrap_or(0) as isize;
+        let new_index = (current + delta).clamp(0, self.timeline.len() as isize - 1);
+        self.timeline_state.select(Some(new_index as usize));
+    }
+
+    /// Moves to the newest timeline entry
+    pub fn first_timeline_entry(&mut self) {
+        if !self.timeline.is_empty() {
+            self.timeline_state.select(Some(0));
+        }
+    }
+
+    /// Moves to the oldest timeline entry
+    pub fn last_timeline_entry(&mut self) {
+        if !self.timeline.is_empty() {
+            self.timeline_state.select(Some(self.timeline.len() - 1));
+        }
+    }
+
+    /// Returns to the package list with the package of the selected timeline entry selected
+    pub fn open_selected_timeline_entry(&mut self) {
+        let Some(entry) = self
+            .timeline_state
+            .selected()
+            .and_then(|i| self.timeline.get(i))
+        else {
+            return;
+        };
+        let package_name = entry.package_name.clone();
+        self.show_package_in_list(&package_name);
+    }
+
+    /// Returns to the package list with the given package selected
+    fn show_package_in_list(&mut self, package_name: &str) {
+        if self.is_searching {
+            self.end_search();
+        }
+
+        // Clear the quick filters if they hide the package
+        if !self.select_package_by_name(package_name) {
+            self.set_filters(QuickFilters::default());
+            self.select_package_by_name(package_name);
+        }
+        self.reset_scroll();
+        self.screen = Screen::Packages;
+    }
+
+    /// Opens the operation history screen, or returns to the package list if it is already open
+    pub fn toggle_history(&mut self) {
+        if self.screen == Screen::History {
+            self.screen = Screen::Packages;
+            return;
+        }
+
+        self.operations = operation_log::load();
+        self.operations_filter = None;
+        self.operations_state.select(if self.operations.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+        self.screen = Screen::History;
+    }
+
+    /// Gets the operations shown on the history screen, newest first
+    pub fn shown_operations(&self) -> Vec<&OperationRecord> {
+        self.operations
+            .iter()
+            .filter(|record| {
+                self.operations_filter
+                    .as_ref()
+                    .is_none_or(|package| &record.package == package)
+            })
+            .collect()
+    }
+
+    /// Gets the selected entry of the history screen
+    pub fn selected_operation(&self) -> Option<&OperationRecord> {
+        self.operations_state
+            .selected()
+            .and_then(|i| self.shown_operations().get(i).copied())
+    }
+
+    /// Moves the history selection by the given number of entries, clamping at both ends
+    pub fn move_history_selection(&mut self, delta: isize) {
+        let count = self.shown_operations().len();
+        if count == 0 {
+            return;
+        }
+
+        let current = self.operations_state.selected().unwrap_or(0) as isize;
+        let new_index = current.saturating_add(delta).clamp(0, count as isize - 1);
+        self.operations_state.select(Some(new_index as usize));
+    }
+
+    /// Narrows the history to the package of the selected entry, or shows every package again,
+    /// keeping the selected entry selected
+    pub fn toggle_history_filter(&mut self) {
+        let Some(selected) = self.selected_operation().cloned() else {
+            return;
+        };
+        self.operations_filter = match self.operations_filter {
+            Some(_) => None,
+            None => Some(selected.package.clone()),
+        };
+        let index = self
+            .shown_operations()
+            .iter()
+            .position(|record| **record == selected);
+        self.operations_state.select(index.or(Some(0)));
+    }
+
+    /// Returns to the package list with the package of the selected history entry selected
+    pub fn open_selected_history_entry(&mut self) {
+        let Some(package_name) = self
+            .selected_operation()
+            .map(|record| record.package.clone())
+        else {
+            return;
+        };
+        if self.items.iter().any(|pkg| pkg.name == package_name) {
+            self.show_package_in_list(&package_name);
+        } else {
+            self.add_status_message(format!("{} is not installed", package_name));
+        }
+    }
+
+    /// Shows the help overlay with the bindings of the given mode
+    pub fn show_help(&mut self, mode: KeyMode) {
+        self.help_mode = mode;
+        self.help_scroll = 0;
+        self.modal_state = ModalState::Help;
+    }
+
+    /// Returns true if the help overlay has more bindings than fit on the screen
+    pub fn help_scrolls(&self) -> bool {
+        self.keymap.bindings(self.help_mode).len() > self.help_rows
+    }
+
+    /// Scrolls the help overlay by a number of bindings, staying within the list
+    pub fn scroll_help(&mut self, delta: isize) {
+        let last = self
+            .keymap
+            .bindings(self.help_mode)
+            .len()
+            .saturating_sub(self.help_rows);
+        self.help_scroll = self.help_scroll.saturating_add_signed(delta).min(last);
+    }
+
+    /// Closes the help overlay
+    pub fn close_help(&mut self) {
+        self.modal_state = ModalState::None;
+    }
+
     /// Sets the quit flag
     pub fn quit(&mut self) {
         self.should_quit = true;
@@ -800,7 +2007,7 @@ impl App {
         // Clean up old messages (older than 10 seconds)
         let now = Instant::now();
         while let Some((_, timestamp)) = self.status_messages.front() {
-            if now.duration_since(*timestamp) > Duration::from_secs(10) {
+            if now.duration_since(*timestamp) > self.config.status_timeout() {
                 self.status_messages.pop_front();
             } else {
                 break;
@@ -811,17 +2018,19 @@ impl App {
         self.status_messages.back().map(|(msg, _)| msg.clone())
     }
 
-    /// Confirms the uninstall operation
+    /// Confirms the uninstall operation, uninstalling the pending packages one after another
     pub fn confirm_uninstall(&mut self) {
-        if let Some(package_name) = self.pending_uninstall_package.take() {
+        let mut packages = std::mem::take(&mut self.pending_uninstall).into_iter();
+        if let Some(package_name) = packages.next() {
             self.modal_state = ModalState::None;
+            self.operation_queue = packages.collect();
             self.start_mock_uninstall(package_name);
         }
     }
 
     /// Cancels the uninstall operation
     pub fn cancel_uninstall(&mut self) {
-        self.pending_uninstall_package = None;
+        self.pending_uninstall.clear();
         self.modal_state = ModalState::None;
         self.add_status_message("Uninstall cancelled".to_string());
     }
@@ -830,13 +2039,14 @@ impl App {
     fn refresh_single_package(&mut self, package_name: String) -> Result<()> {
         match self.repository.refresh_package(&package_name) {
             Ok(Some(updated_package)) => {
-                // Update the package in our main list
-                if let Some(index) = self.items.iter().position(|p| p.name == package_name) {
-                    self.items[index] = updated_package.clone();
+                // Update the package in our main list, adding it back after a reinstall
+                match self.items.iter().position(|p| p.name == package_name) {
+                    Some(index) => self.items[index] = updated_package.clone(),
+                    None => self.items.push(updated_package.clone()),
                 }
 
                 // Update the package in filtered list if we're searching
-                if self.is_searching
+                if self.is_filtering()
                     && let Some(index) = self
                         .filtered_items
                         .iter()
@@ -850,7 +2060,7 @@ impl App {
             Ok(None) => {
                 // Package not found (maybe uninstalled)
                 self.items.retain(|p| p.name != package_name);
-                if self.is_searching {
+                if self.is_filtering() {
                     self.filtered_items.retain(|p| p.name != package_name);
                 }
                 self.add_status_message(format!("📦 {} no longer found", package_name));
@@ -862,3 +2072,32 @@ impl App {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_reload_keeps_the_cached_packages() {
+        let cached = vec![PackageInfo::fixture("jq"), PackageInfo::fixture("wget")];
+        let mut app =
+            App::new(HomebrewRepository::from_packages(cached), Config::default()).unwrap();
+        app.stale_since = Some(1727000000);
+        app.refreshed_at = 1727000000;
+
+        let (tx, rx) = mpsc::channel();
+        tx.send(Err(anyhow::anyhow!(
+            "brew info --json=v2 --installed command failed"
+        )))
+        .unwrap();
+        app.background_load = Some(rx);
+        app.poll_background_reload();
+
+        let names: Vec<&str> = app.items.iter().map(|pkg| pkg.name.as_str()).collect();
+        assert_eq!(names, ["jq", "wget"]);
+        assert!(!app.is_reloading());
+        assert_eq!(app.stale_since, Some(1727000000));
+        assert_eq!(app.refreshed_at, 1727000000);
+        assert!(app.get_current_status().unwrap().contains("command failed"));
+    }
+}
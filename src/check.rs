@@ -0,0 +1,218 @@
+use crate::entities::package_info::PackageInfo;
+use anyhow::Result;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Exit code bit set when packages are outdated and `--fail-on-outdated` is given
+pub const EXIT_OUTDATED: u8 = 2;
+/// Exit code bit set when packages are deprecated and `--fail-on-deprecated` is given
+pub const EXIT_DEPRECATED: u8 = 4;
+/// Exit code bit set when required packages are not installed
+pub const EXIT_MISSING: u8 = 8;
+
+/// Which findings make the check fail
+#[derive(Debug, Clone, Default)]
+pub struct CheckOptions {
+    pub fail_on_outdated: bool,
+    pub fail_on_deprecated: bool,
+    pub required: Vec<String>,
+}
+
+/// The result of checking the installed packages
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub checked: usize,
+    pub outdated: Vec<String>,   // Formatted as `name installed -> current`
+    pub deprecated: Vec<String>, // Package names
+    pub missing: Vec<String>,    // Required package names that are not installed
+}
+
+impl CheckReport {
+    /// Checks the packages, looking up required packages in the names of everything installed
+    pub fn build(
+        packages: &[PackageInfo],
+        installed_names: &HashSet<String>,
+        required: &[String],
+    ) -> Self {
+        Self {
+            checked: packages.len(),
+            outdated: packages
+                .iter()
+                .filter(|pkg| pkg.has_update_available())
+                .map(|pkg| {
+                    format!(
+                        "{} {} -> {}",
+                        pkg.name,
+                        pkg.installed_version.as_deref().unwrap_or("?"),
+                        pkg.current_version
+                    )
+                })
+                .collect(),
+            deprecated: packages
+                .iter()
+                .filter(|pkg| pkg.deprecated)
+                .map(|pkg| pkg.name.clone())
+                .collect(),
+            missing: required
+                .iter()
+                .filter(|name| !installed_names.contains(*name))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Gets the process exit code: 0 on success, otherwise the bits of every failed check
+    pub fn exit_code(&self, options: &CheckOptions) -> u8 {
+        let mut code = 0;
+        if options.fail_on_outdated && !self.outdated.is_empty() {
+            code |= EXIT_OUTDATED;
+        }
+        if options.fail_on_deprecated && !self.deprecated.is_empty() {
+            code |= EXIT_DEPRECATED;
+        }
+        if !self.missing.is_empty() {
+            code |= EXIT_MISSING;
+        }
+        code
+    }
+
+    /// Renders a concise, plain-text report
+    pub fn render(&self, options: &CheckOptions) -> String {
+        let mut output = format!("Checked {} packages\n", self.checked);
+
+        let sections = [
+            ("Outdated", &self.outdated, options.fail_on_outdated),
+            ("Deprecated", &self.deprecated, options.fail_on_deprecated),
+            ("Missing", &self.missing, !options.required.is_empty()),
+        ];
+        for (label, entries, enforced) in sections {
+            let marker = match (entries.is_empty(), enforced) {
+                (true, _) => "ok",
+                (false, true) => "FAIL",
+                (false, false) => "warn",
+            };
+            output.push_str(&format!("{}: {} [{}]\n", label, entries.len(), marker));
+            for entry in entries {
+                output.push_str(&format!("  {}\n", entry));
+            }
+        }
+
+        let exit_code = self.exit_code(options);
+        output.push_str(if exit_code == 0 {
+            "Result: passed\n"
+        } else {
+            "Result: failed\n"
+        });
+        output
+    }
+}
+
+/// Reads required package names from a file with one name per line.
+/// Blank lines and lines starting with `#` are ignored.
+pub fn read_required_file(path: &Path) -> Result<Vec<String>> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    Ok(parse_required_list(&contents))
+}
+
+/// Parses a list of required package names, one per line
+fn parse_required_list(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report(required: &[String]) -> CheckReport {
+        let packages = vec![
+            PackageInfo::fixture("jq").with_versions("1.7.1", "1.7.1"),
+            PackageInfo::fixture("wget").with_versions("1.21", "1.24"),
+            PackageInfo::fixture("youtube-dl")
+                .with_versions("2021.12.17", "2021.12.17")
+                .with_deprecated(true),
+        ];
+        let installed: HashSet<String> = ["jq", "wget", "youtube-dl", "openssl@3"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        CheckReport::build(&packages, &installed, required)
+    }
+
+    #[test]
+    fn test_build_report() {
+        let required = vec!["openssl@3".to_string(), "git".to_string()];
+        let report = sample_report(&required);
+
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.outdated, vec!["wget 1.21 -> 1.24"]);
+        assert_eq!(report.deprecated, vec!["youtube-dl"]);
+        assert_eq!(report.missing, vec!["git"]);
+    }
+
+    #[test]
+    fn test_exit_code_depends_on_flags() {
+        let report = sample_report(&[]);
+
+        assert_eq!(report.exit_code(&CheckOptions::default()), 0);
+        assert_eq!(
+            report.exit_code(&CheckOptions {
+                fail_on_outdated: true,
+                ..Default::default()
+            }),
+            EXIT_OUTDATED
+        );
+        assert_eq!(
+            report.exit_code(&CheckOptions {
+                fail_on_outdated: true,
+                fail_on_deprecated: true,
+                ..Default::default()
+            }),
+            EXIT_OUTDATED | EXIT_DEPRECATED
+        );
+    }
+
+    #[test]
+    fn test_missing_required_packages_always_fail() {
+        let required = vec!["git".to_string()];
+        let report = sample_report(&required);
+        let options = CheckOptions {
+            required,
+            ..Default::default()
+        };
+
+        assert_eq!(report.exit_code(&options), EXIT_MISSING);
+    }
+
+    #[test]
+    fn test_render_report() {
+        let required = vec!["git".to_string()];
+        let report = sample_report(&required);
+        let options = CheckOptions {
+            fail_on_outdated: true,
+            fail_on_deprecated: false,
+            required,
+        };
+
+        assert_eq!(
+            report.render(&options),
+            "Checked 3 packages\n\
+             Outdated: 1 [FAIL]\n  wget 1.21 -> 1.24\n\
+             Deprecated: 1 [warn]\n  youtube-dl\n\
+             Missing: 1 [FAIL]\n  git\n\
+             Result: failed\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_required_list() {
+        let contents = "# Team tools\njq\n\n  wget  \n# ripgrep\n";
+        assert_eq!(parse_required_list(contents), vec!["jq", "wget"]);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::export::ExportFormat;
+use crate::filters::QuickFilters;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+/// A terminal UI for exploring and managing your Homebrew packages
+#[derive(Debug, Parser)]
+#[command(name = "brew-explorer", version, about)]
+pub struct Cli {
+    /// Skip running `brew update` before loading packages
+    #[arg(long, global = true)]
+    pub no_update: bool,
+
+    /// Only show packages with an update available
+    #[arg(long, global = true)]
+    pub outdated: bool,
+
+    /// Only show casks
+    #[arg(long, global = true, conflicts_with = "formulae")]
+    pub casks: bool,
+
+    /// Only show formulae
+    #[arg(long, global = true)]
+    pub formulae: bool,
+
+    /// Only show pinned formulae
+    #[arg(long, global = true)]
+    pub pinned: bool,
+
+    /// Only show packages from this tap, e.g. homebrew/core
+    #[arg(long, global = true, value_name = "TAP")]
+    pub tap: Option<String>,
+
+    /// Only show packages in this collection
+    #[arg(long, global = true, value_name = "NAME")]
+    pub collection: Option<String>,
+
+    /// Start with a search query
+    #[arg(long, global = true, value_name = "QUERY")]
+    pub search: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Commands that print results without starting the TUI
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List installed packages
+    List,
+    /// List packages with an update available
+    Outdated,
+    /// Print installed packages as a Brewfile, JSON, CSV or Markdown. With --collection, a
+    /// Brewfile is a fragment for that collection.
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Brewfile)]
+        format: ExportFormat,
+    },
+    /// Check installed packages and exit non-zero if a check fails
+    Check {
+        /// Fail when packages have an update available
+        #[arg(long)]
+        fail_on_outdated: bool,
+
+        /// Fail when packages are deprecated or disabled
+        #[arg(long)]
+        fail_on_deprecated: bool,
+
+        /// Fail when any of these packages is not installed (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "NAMES")]
+        require: Vec<String>,
+
+        /// Fail when any package listed in this file (one per line) is not installed
+        #[arg(long, value_name = "FILE")]
+        require_file: Option<PathBuf>,
+    },
+    /// Compare the installed packages with a JSON inventory exported on another machine.
+    /// Filters and the search query do not apply.
+    Compare {
+        /// Inventory written by `export --format json`
+        file: PathBuf,
+
+        /// Install the packages that are only in the inventory
+        #[arg(long)]
+        install_missing: bool,
+    },
+    /// Save, compare and restore snapshots of every installed package. Filters and the
+    /// search query do not apply.
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotCommand,
+    },
+}
+
+/// What to do with snapshots
+#[derive(Debug, Subcommand)]
+pub enum SnapshotCommand {
+    /// Save the installed packages as a snapshot, replacing any snapshot with the same name
+    Save { name: String },
+    /// List the snapshots
+    List,
+    /// Show how a snapshot differs from another snapshot, or from the installed packages
+    Diff {
+        /// Snapshot to compare
+        from: String,
+        /// Snapshot to compare with, instead of the installed packages
+        to: Option<String>,
+    },
+    /// Install what a snapshot has and uninstall what was installed since
+    Restore {
+        name: String,
+
+        /// Apply the changes instead of only printing them
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Delete a snapshot
+    Delete { name: String },
+}
+
+impl Cli {
+    /// Builds the quick filters selected on the command line
+    pub fn filters(&self) -> QuickFilters {
+        QuickFilters {
+            formulae_only: self.formulae,
+            casks_only: self.casks,
+            outdated_only: self.outdated,
+            pinned_only: self.pinned,
+            tap: self.tap.clone(),
+            collection: self.collection.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flags() {
+        let cli = Cli::parse_from([
+            "brew-explorer",
+            "--no-update",
+            "--casks",
+            "--outdated",
+            "--search",
+            "fire",
+        ]);
+
+        assert!(cli.no_update);
+        assert!(cli.command.is_none());
+        assert_eq!(cli.search.as_deref(), Some("fire"));
+        assert_eq!(
+            cli.filters(),
+            QuickFilters {
+                casks_only: true,
+                outdated_only: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_subcommand_with_global_flags() {
+        let cli = Cli::parse_from([
+            "brew-explorer",
+            "list",
+            "--formulae",
+            "--no-update",
+            "--tap",
+            "homebrew/core",
+            "--collection",
+            "work",
+        ]);
+
+        assert!(matches!(cli.command, Some(Command::List)));
+        assert!(cli.formulae);
+        assert!(cli.no_update);
+        assert_eq!(cli.filters().tap.as_deref(), Some("homebrew/core"));
+        assert_eq!(cli.filters().collection.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_parse_export_format() {
+        let cli = Cli::parse_from(["brew-explorer", "export", "--format", "csv"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Export {
+                format: ExportFormat::Csv
+            })
+        ));
+
+        let cli = Cli::parse_from(["brew-explorer", "export"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Export {
+                format: ExportFormat::Brewfile
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_check() {
+        let cli = Cli::parse_from([
+            "brew-explorer",
+            "check",
+            "--fail-on-outdated",
+            "--require",
+            "jq,wget",
+        ]);
+
+        match cli.command {
+            Some(Command::Check {
+                fail_on_outdated,
+                fail_on_deprecated,
+                require,
+                require_file,
+            }) => {
+                assert!(fail_on_outdated);
+                assert!(!fail_on_deprecated);
+                assert_eq!(require, vec!["jq", "wget"]);
+                assert!(require_file.is_none());
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_snapshot() {
+        let cli = Cli::parse_from(["brew-explorer", "snapshot", "diff", "before", "--no-update"]);
+        match cli.command {
+            Some(Command::Snapshot {
+                action: SnapshotCommand::Diff { from, to },
+            }) => {
+                assert_eq!(from, "before");
+                assert!(to.is_none());
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(cli.no_update);
+
+        let cli = Cli::parse_from(["brew-explorer", "snapshot", "restore", "before", "--yes"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Snapshot {
+                action: SnapshotCommand::Restore { yes: true, .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        let cli = Cli::parse_from([
+            "brew-explorer",
+            "compare",
+            "laptop.json",
+            "--install-missing",
+        ]);
+        match cli.command {
+            Some(Command::Compare {
+                file,
+                install_missing,
+            }) => {
+                assert_eq!(file, PathBuf::from("laptop.json"));
+                assert!(install_missing);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_casks_conflicts_with_formulae() {
+        assert!(Cli::try_parse_from(["brew-explorer", "--casks", "--formulae"]).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::data_store;
+use crate::entities::package_info::PackageInfo;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const COLLECTIONS_FILE_NAME: &str = "collections.json";
+
+/// Named collections of packages, such as "work" or "kubernetes-stack", keyed by collection name
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CollectionStore {
+    collections: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl CollectionStore {
+    /// Loads the collections, or none if there is no file yet
+    pub fn load() -> Result<Self> {
+        data_store::load_json(COLLECTIONS_FILE_NAME)
+    }
+
+    /// Persists the collections
+    pub fn save(&self) -> Result<()> {
+        data_store::save_json(COLLECTIONS_FILE_NAME, self)
+    }
+
+    /// Gets the names of the collections, sorted
+    pub fn names(&self) -> Vec<String> {
+        self.collections.keys().cloned().collect()
+    }
+
+    /// Gets the names of the packages in a collection, sorted
+    pub fn members(&self, collection: &str) -> Vec<String> {
+        self.collections
+            .get(collection)
+            .map(|members| members.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Checks whether a package is in a collection
+    pub fn contains(&self, collection: &str, package: &str) -> bool {
+        self.collections
+            .get(collection)
+            .is_some_and(|members| members.contains(package))
+    }
+
+    /// Adds a package to a collection, creating the collection if needed, or removes it if it
+    /// was already there. A collection left empty is removed. Returns true if it was added.
+    pub fn toggle(&mut self, collection: &str, package: &str) -> bool {
+        let members = self.collections.entry(collection.to_string()).or_default();
+        let added = members.insert(package.to_string());
+        if !added {
+            members.remove(package);
+            if members.is_empty() {
+                self.collections.remove(collection);
+            }
+        }
+        added
+    }
+
+    /// Copies the collections each package is in onto the packages
+    pub fn annotate(&self, packages: &mut [PackageInfo]) {
+        for package in packages {
+            package.collections = self
+                .collections
+                .iter()
+                .filter(|(_, members)| members.contains(&package.name))
+                .map(|(name, _)| name.clone())
+                .collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_membership() {
+        let mut store = CollectionStore::default();
+        assert!(store.toggle("work", "kubectl"));
+        assert!(store.toggle("work", "helm"));
+        assert!(store.toggle("personal", "helm"));
+        assert_eq!(store.members("work"), ["helm", "kubectl"]);
+        assert!(store.contains("personal", "helm"));
+
+        assert!(!store.toggle("personal", "helm"));
+        assert_eq!(store.names(), ["work"]);
+        assert!(store.members("personal").is_empty());
+    }
+
+    #[test]
+    fn test_annotate() {
+        let mut store = CollectionStore::default();
+        store.toggle("work", "helm");
+        store.toggle("kubernetes-stack", "helm");
+
+        let mut packages = vec![PackageInfo::fixture("helm"), PackageInfo::fixture("jq")];
+        store.annotate(&mut packages);
+        assert_eq!(packages[0].collections, ["kubernetes-stack", "work"]);
+        assert!(packages[1].collections.is_empty());
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let mut store = CollectionStore::default();
+        store.toggle("work", "kubectl");
+
+        let json = serde_json::to_string(&store).unwrap();
+        assert_eq!(json, r#"{"work":["kubectl"]}"#);
+        assert_eq!(
+            serde_json::from_str::<CollectionStore>(&json).unwrap(),
+            store
+        );
+    }
+}
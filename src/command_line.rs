@@ -0,0 +1,332 @@
+use crate::export::ExportFormat;
+use crate::filters::QuickFilters;
+use crate::sort::SortMode;
+use anyhow::{Result, bail};
+use clap::ValueEnum;
+
+/// Names of the commands, as completed. `update`, `rm` and `q` are accepted as aliases.
+const COMMANDS: [&str; 7] = [
+    "upgrade",
+    "uninstall",
+    "sort",
+    "filter",
+    "export",
+    "search",
+    "quit",
+];
+
+/// Sort orders by the names `sort` takes
+const SORT_MODES: [(&str, SortMode); 6] = [
+    ("name", SortMode::Name),
+    ("type", SortMode::Type),
+    ("tap", SortMode::Tap),
+    ("date", SortMode::InstallDate),
+    ("outdated", SortMode::OutdatedFirst),
+    ("size", SortMode::Size),
+];
+
+/// Filters by the names `filter` takes
+const FILTERS: [&str; 7] = [
+    "formulae",
+    "casks",
+    "outdated",
+    "pinned",
+    "tap",
+    "collection",
+    "clear",
+];
+
+/// A command typed on the `:` command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Upgrade(Vec<String>),   // The selected package when none are named
+    Uninstall(Vec<String>), // The selected package when none are named
+    Sort(SortMode),
+    Filter(FilterChange),
+    Export(ExportFormat, String), // Format and file
+    Search(String),
+    Quit,
+}
+
+/// A change to the quick filters, made by `filter`
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterChange {
+    Formulae,
+    Casks,
+    Outdated,
+    Pinned,
+    Tap(String),
+    Collection(String),
+    Clear,
+}
+
+impl Command {
+    /// Parses a command line like `upgrade jq wget`, `sort date` or `export brewfile ~/Brewfile`
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut words = input.split_whitespace();
+        let Some(name) = words.next() else {
+            bail!("type a command, such as upgrade, sort or quit");
+        };
+        let args: Vec<String> = words.map(str::to_string).collect();
+
+        Ok(match name {
+            "upgrade" | "update" => Command::Upgrade(args),
+            "uninstall" | "rm" => Command::Uninstall(args),
+            "sort" => match args.as_slice() {
+                [order] => Command::Sort(
+                    SORT_MODES
+                        .iter()
+                        .find(|(name, _)| name == order)
+                        .map(|(_, mode)| *mode)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "sort: unknown order `{}`, expected {}",
+                                order,
+                                sort_names().join(", ")
+                            )
+                        })?,
+                ),
+                _ => bail!("sort: expected one of {}", sort_names().join(", ")),
+            },
+            "filter" => Command::Filter(FilterChange::parse(&args)?),
+            "export" => {
+                // The file is the rest of the line, so it may contain spaces
+                let rest = input.trim_start()[name.len()..].trim();
+                let Some((format, path)) = rest.split_once(char::is_whitespace) else {
+                    bail!("export: expected a format and a file, like `export brewfile ~/Brewfile`")
+                };
+                Command::Export(
+                    ExportFormat::from_str(format, true).map_err(|_| {
+                        anyhow::anyhow!(
+                            "export: unknown format `{}`, expected {}",
+                            format,
+                            export_format_names().join(", ")
+                        )
+                    })?,
+                    path.trim_start().to_string(),
+                )
+            }
+            "search" => Command::Search(args.join(" ")),
+            "quit" | "q" => Command::Quit,
+            _ => bail!("unknown command `{}`", name),
+        })
+    }
+}
+
+impl FilterChange {
+    /// Parses the arguments of `filter`
+    fn parse(args: &[String]) -> Result<Self> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        Ok(match args.as_slice() {
+            ["formulae"] => FilterChange::Formulae,
+            ["casks"] => FilterChange::Casks,
+            ["outdated"] => FilterChange::Outdated,
+            ["pinned"] => FilterChange::Pinned,
+            ["tap", tap] => FilterChange::Tap(tap.to_string()),
+            ["collection", collection] => FilterChange::Collection(collection.to_string()),
+            ["clear"] => FilterChange::Clear,
+            ["tap" | "collection"] => bail!("filter {}: needs a name", args[0]),
+            _ => bail!("filter: expected one of {}", FILTERS.join(", ")),
+        })
+    }
+
+    /// Switches the filter on, or every filter off for `clear`
+    pub fn apply(&self, filters: &mut QuickFilters) {
+        match self {
+            FilterChange::Formulae => {
+                filters.formulae_only = true;
+                filters.casks_only = false;
+            }
+            FilterChange::Casks => {
+                filters.casks_only = true;
+                filters.formulae_only = false;
+            }
+            FilterChange::Outdated => filters.outdated_only = true,
+            FilterChange::Pinned => filters.pinned_only = true,
+            FilterChange::Tap(tap) => filters.tap = Some(tap.clone()),
+            FilterChange::Collection(collection) => filters.collection = Some(collection.clone()),
+            FilterChange::Clear => *filters = QuickFilters::default(),
+        }
+    }
+}
+
+/// Names a command line can complete to, besides the commands themselves
+pub struct CompletionSource<'a> {
+    pub packages: Vec<&'a str>,
+    pub taps: Vec<String>,
+    pub collections: Vec<String>,
+}
+
+/// Gets the sorted completions of the last word of a partly typed command line
+pub fn completions(line: &str, source: &CompletionSource) -> Vec<String> {
+    let mut words: Vec<&str> = line.split_whitespace().collect();
+    // A trailing space starts a new, empty word
+    if line.is_empty() || line.ends_with(char::is_whitespace) {
+        words.push("");
+    }
+    let Some((word, previous)) = words.split_last() else {
+        return Vec::new();
+    };
+
+    let candidates: Vec<String> = match previous {
+        [] => COMMANDS.iter().map(|name| name.to_string()).collect(),
+        ["upgrade" | "update" | "uninstall" | "rm", named @ ..] => source
+            .packages
+            .iter()
+            .filter(|name| !named.contains(name))
+            .map(|name| name.to_string())
+            .collect(),
+        ["sort"] => sort_names(),
+        ["filter"] => FILTERS.iter().map(|name| name.to_string()).collect(),
+        ["filter", "tap"] => source.taps.clone(),
+        ["filter", "collection"] => source.collections.clone(),
+        ["export"] => export_format_names(),
+        _ => Vec::new(),
+    };
+
+    let mut candidates: Vec<String> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(word))
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+/// Gets the longest start shared by every candidate
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some((first, rest)) = candidates.split_first() else {
+        return String::new();
+    };
+    let mut prefix: &str = first;
+    for candidate in rest {
+        while !candidate.starts_with(prefix) {
+            prefix = &prefix[..prefix.char_indices().last().map_or(0, |(index, _)| index)];
+        }
+    }
+    prefix.to_string()
+}
+
+/// Gets the names of the sort orders
+fn sort_names() -> Vec<String> {
+    SORT_MODES
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Gets the names of the export formats
+fn export_format_names() -> Vec<String> {
+    ExportFormat::value_variants()
+        .iter()
+        .filter_map(|format| format.to_possible_value())
+        .map(|value| value.get_name().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> CompletionSource<'static> {
+        CompletionSource {
+            packages: vec!["jq", "jpeg", "wget", "helm"],
+            taps: vec!["homebrew/cask".to_string(), "homebrew/core".to_string()],
+            collections: vec!["work".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            Command::parse("  upgrade jq   wget ").unwrap(),
+            Command::Upgrade(vec!["jq".to_string(), "wget".to_string()])
+        );
+        assert_eq!(Command::parse("rm").unwrap(), Command::Uninstall(vec![]));
+        assert_eq!(
+            Command::parse("sort date").unwrap(),
+            Command::Sort(SortMode::InstallDate)
+        );
+        assert_eq!(
+            Command::parse("filter tap homebrew/cask").unwrap(),
+            Command::Filter(FilterChange::Tap("homebrew/cask".to_string()))
+        );
+        assert_eq!(
+            Command::parse("export Brewfile ~/Brewfile").unwrap(),
+            Command::Export(ExportFormat::Brewfile, "~/Brewfile".to_string())
+        );
+        assert_eq!(
+            Command::parse(" export  csv  ~/My Documents/brew  list.csv ").unwrap(),
+            Command::Export(
+                ExportFormat::Csv,
+                "~/My Documents/brew  list.csv".to_string()
+            )
+        );
+        assert_eq!(
+            Command::parse("search type:cask fire").unwrap(),
+            Command::Search("type:cask fire".to_string())
+        );
+        assert_eq!(Command::parse("q").unwrap(), Command::Quit);
+    }
+
+    #[test]
+    fn test_invalid_commands() {
+        let error = |input: &str| Command::parse(input).unwrap_err().to_string();
+
+        assert!(error("").contains("type a command"));
+        assert!(error("frobnicate").contains("unknown command `frobnicate`"));
+        assert!(error("sort colour").contains("unknown order `colour`"));
+        assert!(error("sort").contains("expected one of name, type"));
+        assert!(error("filter tap").contains("needs a name"));
+        assert!(error("filter big").contains("expected one of formulae"));
+        assert!(error("export yaml out.yaml").contains("unknown format `yaml`"));
+        assert!(error("export json").contains("expected a format and a file"));
+    }
+
+    #[test]
+    fn test_filter_changes() {
+        let mut filters = QuickFilters {
+            formulae_only: true,
+            ..Default::default()
+        };
+        FilterChange::Casks.apply(&mut filters);
+        FilterChange::Outdated.apply(&mut filters);
+        assert_eq!(filters.labels(), vec!["casks", "outdated"]);
+
+        FilterChange::Clear.apply(&mut filters);
+        assert!(!filters.is_active());
+    }
+
+    #[test]
+    fn test_completions() {
+        let source = source();
+        let complete = |line: &str| completions(line, &source);
+
+        assert_eq!(complete("u"), ["uninstall", "upgrade"]);
+        assert_eq!(complete("").len(), COMMANDS.len());
+        assert_eq!(complete("upgrade j"), ["jpeg", "jq"]);
+        assert_eq!(complete("upgrade jq "), ["helm", "jpeg", "wget"]);
+        assert_eq!(complete("sort d"), ["date"]);
+        assert_eq!(complete("filter cl"), ["clear"]);
+        assert_eq!(complete("filter co"), ["collection"]);
+        assert_eq!(complete("filter tap homebrew/c"), source.taps);
+        assert_eq!(complete("filter collection "), ["work"]);
+        assert_eq!(complete("export m"), ["markdown"]);
+        assert!(complete("export json ~/out").is_empty());
+        assert!(complete("quit ").is_empty());
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        let candidates =
+            |names: &[&str]| -> Vec<String> { names.iter().map(|name| name.to_string()).collect() };
+
+        assert_eq!(
+            common_prefix(&candidates(&["homebrew/cask", "homebrew/core"])),
+            "homebrew/c"
+        );
+        assert_eq!(common_prefix(&candidates(&["jq"])), "jq");
+        assert_eq!(common_prefix(&candidates(&["jq", "wget"])), "");
+        assert_eq!(common_prefix(&[]), "");
+    }
+}
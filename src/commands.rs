@@ -0,0 +1,391 @@
+use crate::check::{self, CheckOptions, CheckReport};
+use crate::cli::{Cli, Command, SnapshotCommand};
+use crate::collections::CollectionStore;
+use crate::entities::package_info::PackageInfo;
+use crate::export::{self, ExportFormat};
+use crate::fuzzy;
+use crate::helpers;
+use crate::notes::NoteStore;
+use crate::operation_log::{self, OperationKind, OperationRecord, RemovedPackage};
+use crate::query::Query;
+use crate::repository::{HomebrewRepository, KegSizes};
+use crate::snapshots::{PackageSetDiff, RestorePlan, Snapshot, SnapshotEntry, SnapshotStore};
+use anyhow::Result;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Runs a command that prints its results instead of starting the TUI
+pub fn run(cli: &Cli, command: &Command) -> Result<ExitCode> {
+    let output = match command {
+        Command::List => format_list(&load_packages(cli)?),
+        Command::Outdated => format_outdated(&load_packages(cli)?),
+        Command::Export { format } => {
+            let output = export::format_packages(&load_packages(cli)?, *format)?;
+            match (&cli.collection, format) {
+                // A collection's Brewfile is a fragment to combine with others
+                (Some(collection), ExportFormat::Brewfile) => {
+                    format!("# Collection: {}\n{}", collection, output)
+                }
+                _ => output,
+            }
+        }
+        Command::Check {
+            fail_on_outdated,
+            fail_on_deprecated,
+            require,
+            require_file,
+        } => {
+            let mut required = require.clone();
+            if let Some(path) = require_file {
+                required.extend(check::read_required_file(path)?);
+            }
+            let options = CheckOptions {
+                fail_on_outdated: *fail_on_outdated,
+                fail_on_deprecated: *fail_on_deprecated,
+                required,
+            };
+            let (packages, installed_names) = load_installed_with_names(cli)?;
+            return run_check(&narrow_packages(cli, packages)?, &installed_names, &options);
+        }
+        Command::Compare {
+            file,
+            install_missing,
+        } => return run_compare(cli, file, *install_missing),
+        Command::Snapshot { action } => return run_snapshot(cli, action),
+    };
+    print!("{}", output);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Prints the check report and returns the exit code for the failed checks.
+/// Required packages count as installed even when they were installed as a dependency.
+fn run_check(
+    packages: &[PackageInfo],
+    installed_names: &HashSet<String>,
+    options: &CheckOptions,
+) -> Result<ExitCode> {
+    let report = CheckReport::build(packages, installed_names, &options.required);
+    print!("{}", report.render(options));
+
+    Ok(ExitCode::from(report.exit_code(options)))
+}
+
+/// Prints how an inventory from another machine differs from the installed packages, and
+/// installs what is missing here if asked to
+fn run_compare(cli: &Cli, file: &Path, install_missing: bool) -> Result<ExitCode> {
+    let contents = fs::read_to_string(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file.display(), e))?;
+    let inventory = export::parse_inventory(&contents)?;
+    let theirs: Vec<SnapshotEntry> = inventory.packages.iter().map(SnapshotEntry::from).collect();
+    let (installed, installed_names) = load_installed_with_names(cli)?;
+    let mut diff = PackageSetDiff::between(&theirs, &installed_entries(&installed));
+    diff.set_aside_dependencies(&installed_names);
+
+    let file_name = file
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    println!("Comparing {} with the installed packages", file_name);
+    print!(
+        "{}",
+        diff.render(&format!("Only in {}", file_name), "Only installed here")
+    );
+
+    if diff.removed.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    if !install_missing {
+        let noun = if diff.removed.len() == 1 {
+            "package"
+        } else {
+            "packages"
+        };
+        println!(
+            "Run again with --install-missing to install the {} {} missing here",
+            diff.removed.len(),
+            noun
+        );
+        return Ok(ExitCode::SUCCESS);
+    }
+    let plan = RestorePlan {
+        install: diff.removed,
+        uninstall: Vec::new(),
+        inexact: Vec::new(),
+        dependencies: diff.dependencies,
+    };
+    Ok(if apply_plan(&plan, &[]) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Runs a snapshot command, printing what it did
+fn run_snapshot(cli: &Cli, action: &SnapshotCommand) -> Result<ExitCode> {
+    let mut store = SnapshotStore::load()?;
+    let find = |store: &SnapshotStore, name: &str| -> Result<Snapshot> {
+        store
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No snapshot named {}", name))
+    };
+
+    match action {
+        SnapshotCommand::Save { name } => {
+            let count = store.take(name, &load_installed(cli)?).packages.len();
+            store.save()?;
+            println!("📸 Saved {} packages as {}", count, name);
+        }
+        SnapshotCommand::List => print!("{}", format_snapshots(&store)),
+        SnapshotCommand::Diff { from, to } => {
+            let old = find(&store, from)?;
+            let (new, installed_names, new_heading) = match to {
+                Some(to) => (
+                    find(&store, to)?.packages,
+                    HashSet::new(),
+                    format!("Only in {}", to),
+                ),
+                None => {
+                    let (installed, installed_names) = load_installed_with_names(cli)?;
+                    (
+                        installed_entries(&installed),
+                        installed_names,
+                        "Only installed".to_string(),
+                    )
+                }
+            };
+            let mut diff = PackageSetDiff::between(&old.packages, &new);
+            diff.set_aside_dependencies(&installed_names);
+            print!(
+                "{}",
+                diff.render(&format!("Only in {}", from), &new_heading)
+            );
+        }
+        SnapshotCommand::Restore { name, yes } => {
+            let snapshot = find(&store, name)?;
+            let (installed, installed_names) = load_installed_with_names(cli)?;
+            let plan =
+                RestorePlan::new(&snapshot, &installed_entries(&installed), &installed_names);
+            print!("{}", plan.render());
+            if !plan.is_empty() {
+                if !yes {
+                    println!("Run again with --yes to make these changes");
+                } else if !apply_plan(&plan, &installed) {
+                    return Ok(ExitCode::FAILURE);
+                }
+            }
+        }
+        SnapshotCommand::Delete { name } => {
+            if !store.remove(name) {
+                anyhow::bail!("No snapshot named {}", name);
+            }
+            store.save()?;
+            println!("Deleted snapshot {}", name);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Installs and uninstalls the packages of a plan, logging each operation. Returns false if
+/// any of them failed.
+fn apply_plan(plan: &RestorePlan, installed: &[PackageInfo]) -> bool {
+    let repository = HomebrewRepository::from_packages(Vec::new());
+    let mut succeeded = true;
+    let mut report = |record: OperationRecord| {
+        if let Err(e) = operation_log::append(&record) {
+            eprintln!("⚠️  Failed to write the operation log: {}", e);
+        }
+        if !record.succeeded() {
+            succeeded = false;
+            let last_line = record
+                .output
+                .lines()
+                .rev()
+                .find(|line| !line.trim().is_empty());
+            eprintln!(
+                "❌ Failed to {} {}: {}",
+                if record.kind == OperationKind::Install {
+                    "install"
+                } else {
+                    "uninstall"
+                },
+                record.package,
+                last_line.unwrap_or("no output")
+            );
+        }
+        record.succeeded()
+    };
+
+    for entry in &plan.install {
+        println!("Installing {}...", entry.name);
+        let output =
+            repository.install_package(&entry.name, &entry.package_type, entry.tap.as_deref(), &[]);
+        let record = OperationRecord::now(
+            OperationKind::Install,
+            &entry.name,
+            output.exit_code,
+            output.output,
+        );
+        if report(record)
+            && entry.pinned
+            && let Err(e) = repository.pin_package(&entry.name, true)
+        {
+            eprintln!("⚠️  {}", e);
+        }
+    }
+    for entry in &plan.uninstall {
+        println!("Uninstalling {}...", entry.name);
+        let output = repository.uninstall_package(&entry.name);
+        let mut record = OperationRecord::now(
+            OperationKind::Uninstall,
+            &entry.name,
+            output.exit_code,
+            output.output,
+        );
+        // Logged like uninstalls in the TUI, so they can be undone from the history
+        let package = installed.iter().find(|pkg| pkg.name == entry.name);
+        record.from_version = entry.version.clone();
+        record.removed = package
+            .map(RemovedPackage::from_package)
+            .filter(|_| output.exit_code == Some(0));
+        report(record);
+    }
+    succeeded
+}
+
+/// Formats the snapshots as aligned `name  date  count` lines
+fn format_snapshots(store: &SnapshotStore) -> String {
+    let name_width = store.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let lines: String = store
+        .iter()
+        .map(|(name, snapshot)| {
+            format!(
+                "{:<name_width$}  {} UTC  {} packages\n",
+                name,
+                helpers::format_date(snapshot.taken_at),
+                snapshot.packages.len()
+            )
+        })
+        .collect();
+    if lines.is_empty() {
+        "No snapshots yet. Save one with `brew-explorer snapshot save NAME`\n".to_string()
+    } else {
+        lines
+    }
+}
+
+/// Describes installed packages as entries of an installed set
+fn installed_entries(packages: &[PackageInfo]) -> Vec<SnapshotEntry> {
+    packages.iter().map(SnapshotEntry::from_package).collect()
+}
+
+/// Loads every installed package, ignoring the filters and search query
+fn load_installed(cli: &Cli) -> Result<Vec<PackageInfo>> {
+    Ok(load_installed_with_names(cli)?.0)
+}
+
+/// Loads every installed package along with the names of every installed formula and cask,
+/// including formulae installed as dependencies
+fn load_installed_with_names(cli: &Cli) -> Result<(Vec<PackageInfo>, HashSet<String>)> {
+    if !cli.no_update && helpers::brew_update().is_err() {
+        eprintln!("⚠️  brew update failed, package information may be out of date");
+    }
+
+    HomebrewRepository::fetch_installed_packages(&KegSizes::default())
+        .map_err(|e| anyhow::anyhow!("Failed to load packages from Homebrew: {}", e))
+}
+
+/// Loads installed packages, narrowed by the filters and search query given on the command line
+fn load_packages(cli: &Cli) -> Result<Vec<PackageInfo>> {
+    narrow_packages(cli, load_installed(cli)?)
+}
+
+/// Narrows packages by the filters and search query given on the command line
+fn narrow_packages(cli: &Cli, mut packages: Vec<PackageInfo>) -> Result<Vec<PackageInfo>> {
+    let filters = cli.filters();
+    NoteStore::load()?.annotate(&mut packages);
+    CollectionStore::load()?.annotate(&mut packages);
+    packages.retain(|pkg| filters.matches(pkg));
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Best matches first, in name order when they match equally well
+    let query = Query::parse(cli.search.as_deref().unwrap_or_default())
+        .map_err(|e| anyhow::anyhow!("Invalid search query: {}", e))?;
+    let packages = packages.iter().filter(|pkg| query.matches(pkg));
+    Ok(fuzzy::rank(packages, &query.text)
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+/// Formats packages as aligned `name  type  version` lines
+fn format_list(packages: &[PackageInfo]) -> String {
+    let name_width = packages.iter().map(|pkg| pkg.name.len()).max().unwrap_or(0);
+
+    packages
+        .iter()
+        .map(|pkg| {
+            format!(
+                "{:<name_width$}  {:<7}  {}\n",
+                pkg.name,
+                pkg.package_type.label(),
+                pkg.installed_version.as_deref().unwrap_or("-"),
+            )
+        })
+        .collect()
+}
+
+/// Formats outdated packages as `name installed -> current` lines
+fn format_outdated(packages: &[PackageInfo]) -> String {
+    let outdated: Vec<&PackageInfo> = packages
+        .iter()
+        .filter(|pkg| pkg.has_update_available())
+        .collect();
+    let name_width = outdated.iter().map(|pkg| pkg.name.len()).max().unwrap_or(0);
+
+    outdated
+        .iter()
+        .map(|pkg| {
+            format!(
+                "{:<name_width$}  {} -> {}\n",
+                pkg.name,
+                pkg.installed_version.as_deref().unwrap_or("-"),
+                pkg.current_version,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::package_info::PackageType;
+
+    #[test]
+    fn test_format_list() {
+        let packages = vec![
+            PackageInfo::fixture("jq").with_versions("1.7.1", "1.7.1"),
+            PackageInfo::fixture("firefox")
+                .with_type(PackageType::Cask)
+                .with_versions("130.0", "130.0"),
+        ];
+
+        assert_eq!(
+            format_list(&packages),
+            "jq       formula  1.7.1\nfirefox  cask     130.0\n"
+        );
+    }
+
+    #[test]
+    fn test_format_outdated_only_lists_packages_with_updates() {
+        let packages = vec![
+            PackageInfo::fixture("jq").with_versions("1.7.1", "1.7.1"),
+            PackageInfo::fixture("wget").with_versions("1.21", "1.24"),
+        ];
+
+        assert_eq!(format_outdated(&packages), "wget  1.21 -> 1.24\n");
+    }
+}
@@ -0,0 +1,332 @@
+use crate::keymap::{KeyMode, Keymap};
+use crate::theme::{THEME_NAMES, Theme};
+use anyhow::Result;
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// The longest background refresh interval, a day
+const MAX_REFRESH_INTERVAL_MINS: u64 = 24 * 60;
+
+/// User configuration, loaded from `config.toml` in the XDG config directory.
+/// Every setting is optional and falls back to the built-in default.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub layout: LayoutConfig,
+    pub timing: TimingConfig,
+    pub theme: Option<String>, // A built-in theme or one defined under `[themes]`, "dark" if unset
+    pub themes: BTreeMap<String, CustomTheme>,
+    pub colours: ColourConfig,
+    pub keys: KeysConfig,
+}
+
+/// Sizes of the main screen areas
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutConfig {
+    pub list_width_percent: u16, // The details panel takes the rest
+    pub max_columns: usize,      // Most package list columns shown side by side
+}
+
+/// Event loop and status bar timings
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimingConfig {
+    pub tick_rate_ms: u64,
+    pub status_timeout_secs: u64,
+    pub refresh_interval_mins: u64, // 0 turns the background refresh off
+}
+
+/// Colours that replace those of the theme, one per theme colour
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColourConfig {
+    pub outdated: Option<Colour>,
+    pub up_to_date: Option<Colour>,
+    pub highlight: Option<Colour>,
+    pub status_bar_background: Option<Colour>,
+    pub status_bar_foreground: Option<Colour>,
+    pub accent: Option<Colour>,
+    pub warning: Option<Colour>,
+    pub danger: Option<Colour>,
+    pub success: Option<Colour>,
+    pub muted: Option<Colour>,
+    pub faint: Option<Colour>,
+    pub link: Option<Colour>,
+    pub modal_border: Option<Colour>,
+    pub modal_background: Option<Colour>,
+}
+
+/// A named theme defined in the config file, starting from a built-in theme
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomTheme {
+    #[serde(default = "default_theme_base")]
+    pub base: String,
+    #[serde(default)]
+    pub colours: ColourConfig,
+}
+
+fn default_theme_base() -> String {
+    "dark".to_string()
+}
+
+/// Key binding overrides per mode, mapping action names to lists of keys
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    pub normal: BTreeMap<String, Vec<String>>,
+    pub search: BTreeMap<String, Vec<String>>,
+    pub timeline: BTreeMap<String, Vec<String>>,
+    pub history: BTreeMap<String, Vec<String>>,
+    pub confirm: BTreeMap<String, Vec<String>>,
+    pub progress: BTreeMap<String, Vec<String>>,
+    pub prompt: BTreeMap<String, Vec<String>>,
+    pub picker: BTreeMap<String, Vec<String>>,
+    pub command: BTreeMap<String, Vec<String>>,
+}
+
+impl KeysConfig {
+    /// Gets the overrides of one mode
+    pub fn mode(&self, mode: KeyMode) -> &BTreeMap<String, Vec<String>> {
+        match mode {
+            KeyMode::Normal => &self.normal,
+            KeyMode::Search => &self.search,
+            KeyMode::Timeline => &self.timeline,
+            KeyMode::History => &self.history,
+            KeyMode::Confirm => &self.confirm,
+            KeyMode::Progress => &self.progress,
+            KeyMode::Prompt => &self.prompt,
+            KeyMode::Picker => &self.picker,
+            KeyMode::Command => &self.command,
+        }
+    }
+}
+
+/// A colour written as a name ("green", "light-blue"), an RGB hex code ("#dc5050")
+/// or a 256-colour palette index ("208")
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Colour(pub Color);
+
+impl TryFrom<String> for Colour {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Color::from_str(&value).map(Colour).map_err(|_| {
+            format!(
+                "invalid colour \"{}\", expected a name like \"green\", a hex code like \"#dc5050\" or a number from 0 to 255",
+                value
+            )
+        })
+    }
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            list_width_percent: 40,
+            max_columns: 4,
+        }
+    }
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            tick_rate_ms: 100,
+            status_timeout_secs: 10,
+            refresh_interval_mins: 30,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file, or the defaults if there is none.
+    /// Fails with a message naming the file and the offending key if it is invalid.
+    pub fn load() -> Result<Self> {
+        let Some(path) = config_file_path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))
+    }
+
+    /// Parses and validates the contents of a config file
+    pub fn parse(contents: &str) -> Result<Self> {
+        let config: Self = toml::from_str(contents)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that every value is within a usable range
+    fn validate(&self) -> Result<()> {
+        if !(10..=90).contains(&self.layout.list_width_percent) {
+            return Err(anyhow::anyhow!(
+                "layout.list_width_percent must be between 10 and 90, got {}",
+                self.layout.list_width_percent
+            ));
+        }
+        if !(1..=8).contains(&self.layout.max_columns) {
+            return Err(anyhow::anyhow!(
+                "layout.max_columns must be between 1 and 8, got {}",
+                self.layout.max_columns
+            ));
+        }
+        if !(10..=1000).contains(&self.timing.tick_rate_ms) {
+            return Err(anyhow::anyhow!(
+                "timing.tick_rate_ms must be between 10 and 1000, got {}",
+                self.timing.tick_rate_ms
+            ));
+        }
+        if self.timing.status_timeout_secs == 0 {
+            return Err(anyhow::anyhow!(
+                "timing.status_timeout_secs must be at least 1"
+            ));
+        }
+        if self.timing.refresh_interval_mins > MAX_REFRESH_INTERVAL_MINS {
+            return Err(anyhow::anyhow!(
+                "timing.refresh_interval_mins must be between 0 and {}, got {}",
+                MAX_REFRESH_INTERVAL_MINS,
+                self.timing.refresh_interval_mins
+            ));
+        }
+        for (name, custom) in &self.themes {
+            if Theme::built_in(&custom.base).is_none() {
+                return Err(anyhow::anyhow!(
+                    "themes.{}.base must be one of {}, got \"{}\"",
+                    name,
+                    THEME_NAMES.join(", "),
+                    custom.base
+                ));
+            }
+        }
+        Theme::from_config(self)?;
+        Keymap::new(&self.keys)?;
+        Ok(())
+    }
+
+    /// Gets the event loop tick rate
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.timing.tick_rate_ms)
+    }
+
+    /// Gets how long status messages stay in the status bar
+    pub fn status_timeout(&self) -> Duration {
+        Duration::from_secs(self.timing.status_timeout_secs)
+    }
+
+    /// Gets how often the package list is refreshed in the background, if it is
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        Some(self.timing.refresh_interval_mins)
+            .filter(|&minutes| minutes > 0)
+            .map(|minutes| Duration::from_secs(minutes.saturating_mul(60)))
+    }
+}
+
+/// Gets the directory Brew Explorer reads its config from:
+/// `$XDG_CONFIG_HOME/brew-explorer`, falling back to `~/.config/brew-explorer`
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+    Some(base.join("brew-explorer"))
+}
+
+/// Gets the path of the config file
+fn config_file_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_uses_defaults() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_parse_full_config() {
+        let config = Config::parse(
+            r##"
+            [layout]
+            list_width_percent = 50
+            max_columns = 2
+
+            [timing]
+            tick_rate_ms = 250
+            status_timeout_secs = 5
+            refresh_interval_mins = 0
+
+            [colours]
+            outdated = "#ff8800"
+            up_to_date = "light-green"
+            highlight = "236"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(config.layout.list_width_percent, 50);
+        assert_eq!(config.layout.max_columns, 2);
+        assert_eq!(config.tick_rate(), Duration::from_millis(250));
+        assert_eq!(config.status_timeout(), Duration::from_secs(5));
+        assert_eq!(config.refresh_interval(), None);
+        assert_eq!(
+            Config::default().refresh_interval(),
+            Some(Duration::from_secs(30 * 60))
+        );
+        assert_eq!(
+            config.colours.outdated,
+            Some(Colour(Color::Rgb(255, 136, 0)))
+        );
+        assert_eq!(config.colours.up_to_date, Some(Colour(Color::LightGreen)));
+        assert_eq!(config.colours.highlight, Some(Colour(Color::Indexed(236))));
+        // Colours that aren't set keep those of the theme
+        assert_eq!(config.colours.status_bar_foreground, None);
+    }
+
+    #[test]
+    fn test_unknown_key_is_reported() {
+        let error = Config::parse("[layout]\nlist_width = 50\n").unwrap_err();
+        assert!(error.to_string().contains("unknown field `list_width`"));
+    }
+
+    #[test]
+    fn test_invalid_colour_is_reported() {
+        let error = Config::parse("[colours]\noutdated = \"reddish\"\n").unwrap_err();
+        assert!(error.to_string().contains("invalid colour \"reddish\""));
+    }
+
+    #[test]
+    fn test_out_of_range_value_is_reported() {
+        let error = Config::parse("[layout]\nmax_columns = 0\n").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("layout.max_columns must be between 1 and 8")
+        );
+
+        let error = Config::parse("[timing]\nrefresh_interval_mins = 1441\n").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("timing.refresh_interval_mins must be between 0 and 1440")
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Gets the directory Brew Explorer keeps its data in, under the user's data directory
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("brew-explorer"))
+}
+
+/// Gets the path of a file in the data directory
+pub fn data_file(file_name: &str) -> Result<PathBuf> {
+    data_dir()
+        .map(|dir| dir.join(file_name))
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the user's data directory"))
+}
+
+/// Loads a JSON file from the data directory, or the default if there is none. Fails if the
+/// file can't be read or parsed, so that saving doesn't overwrite what it holds.
+pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> Result<T> {
+    match data_file(file_name) {
+        Ok(path) => read_json(&path),
+        Err(_) => Ok(T::default()),
+    }
+}
+
+/// Reads a JSON file, or the default if it doesn't exist
+fn read_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => anyhow::bail!("Failed to read {}: {}", path.display(), e),
+    };
+    serde_json::from_str(&contents).map_err(|e| {
+        anyhow::anyhow!(
+            "{} is corrupt ({}). Fix or remove it to continue",
+            path.display(),
+            e
+        )
+    })
+}
+
+/// Writes a value as JSON to a file in the data directory, creating the directory if needed
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> Result<()> {
+    let path = data_file(file_name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_read_json() {
+        let dir = std::env::temp_dir().join(format!("brew-explorer-json-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("notes.json");
+
+        let missing: Result<BTreeMap<String, String>> = read_json(&path);
+        fs::write(&path, r#"{"jq":"for scripts"}"#).unwrap();
+        let valid: Result<BTreeMap<String, String>> = read_json(&path);
+        fs::write(&path, r#"{"jq":"#).unwrap();
+        let corrupt: Result<BTreeMap<String, String>> = read_json(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(missing.unwrap().is_empty());
+        assert_eq!(valid.unwrap()["jq"], "for scripts");
+        assert!(corrupt.unwrap_err().to_string().contains("is corrupt"));
+    }
+}
@@ -27,6 +27,12 @@ nest! {
                     }
                 >,
                 pub outdated: bool,
+                #[serde(default)]
+                pub deprecated: bool,
+                #[serde(default)]
+                pub disabled: bool,
+                #[serde(default)]
+                pub pinned: bool,
                 pub caveats: Option<String>,
             }
         >,
@@ -40,7 +46,13 @@ nest! {
                 pub homepage: Option<String>,
                 pub version: String,
                 pub installed: Option<String>,
+                #[serde(default)]
+                pub installed_time: Option<u64>,
                 pub outdated: bool,
+                #[serde(default)]
+                pub deprecated: bool,
+                #[serde(default)]
+                pub disabled: bool,
                 pub caveats: Option<String>,
             }
         >,
@@ -133,6 +145,77 @@ mod tests {
         assert_eq!(response.casks[0].tap, None);
     }
 
+    #[test]
+    fn test_deserialize_cask_with_installed_time() {
+        let json = r#"{
+            "formulae": [],
+            "casks": [{
+                "token": "test-cask",
+                "tap": "homebrew/cask",
+                "name": ["Test Cask"],
+                "desc": "Test description",
+                "homepage": "https://example.com",
+                "version": "1.0.0",
+                "installed": "1.0.0",
+                "installed_time": 1727000000,
+                "outdated": false,
+                "caveats": null
+            }]
+        }"#;
+
+        let response: BrewInfoResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.casks[0].installed_time, Some(1727000000));
+    }
+
+    #[test]
+    fn test_deserialize_cask_without_installed_time() {
+        let json = r#"{
+            "formulae": [],
+            "casks": [{
+                "token": "test-cask",
+                "tap": "homebrew/cask",
+                "name": ["Test Cask"],
+                "desc": "Test description",
+                "homepage": "https://example.com",
+                "version": "1.0.0",
+                "installed": "1.0.0",
+                "outdated": false,
+                "caveats": null
+            }]
+        }"#;
+
+        let response: BrewInfoResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.casks[0].installed_time, None);
+    }
+
+    #[test]
+    fn test_deserialize_deprecated_formula() {
+        let json = r#"{
+            "formulae": [{
+                "name": "test-formula",
+                "tap": "homebrew/core",
+                "desc": "Test description",
+                "homepage": "https://example.com",
+                "versions": {
+                    "stable": "1.0.0",
+                    "head": null
+                },
+                "installed": [],
+                "outdated": false,
+                "deprecated": true,
+                "disabled": false,
+                "pinned": true,
+                "caveats": null
+            }],
+            "casks": []
+        }"#;
+
+        let response: BrewInfoResponse = serde_json::from_str(json).unwrap();
+        assert!(response.formulae[0].deprecated);
+        assert!(!response.formulae[0].disabled);
+        assert!(response.formulae[0].pinned);
+    }
+
     #[test]
     fn test_deserialize_formula_with_null_time() {
         let json = r#"{
@@ -0,0 +1,182 @@
+use anyhow::Result;
+use nestify::nest;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+nest! {
+    /// The subset of a keg's INSTALL_RECEIPT.json that Brew Explorer cares about
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    pub struct InstallReceipt {
+        #[serde(default)]
+        pub time: Option<u64>,
+        #[serde(default)]
+        pub poured_from_bottle: bool,
+        #[serde(default)]
+        pub built_as_bottle: bool,
+        #[serde(default)]
+        pub used_options: Vec<String>,
+        #[serde(default)]
+        pub source: Option<
+            #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+            pub struct ReceiptSource {
+                #[serde(default)]
+                pub spec: Option<String>,
+            }
+        >,
+        #[serde(default)]
+        pub runtime_dependencies: Option<Vec<
+            #[derive(Debug, Clone, Serialize, Deserialize)]
+            pub struct RuntimeDependency {
+                pub full_name: String,
+                pub version: String,
+            }
+        >>,
+    }
+}
+
+/// An installed keg (one version directory in the Cellar) together with its receipt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KegReceipt {
+    pub version: String,
+    pub receipt: InstallReceipt,
+}
+
+impl InstallReceipt {
+    /// Parses the contents of an INSTALL_RECEIPT.json file
+    pub fn parse(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Reads and parses an INSTALL_RECEIPT.json file from disk
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Self::parse(&contents)
+    }
+
+    /// Returns the build spec (stable, head, devel) the keg was installed from
+    pub fn spec(&self) -> Option<&str> {
+        self.source
+            .as_ref()
+            .and_then(|source| source.spec.as_deref())
+    }
+
+    /// Returns the runtime dependencies recorded at install time, formatted as `name version`
+    pub fn runtime_dependency_names(&self) -> Vec<String> {
+        self.runtime_dependencies
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|dep| format!("{} {}", dep.full_name, dep.version))
+            .collect()
+    }
+
+    /// Reads the receipts of every keg of a formula, sorted oldest first.
+    /// Kegs without a readable receipt are skipped.
+    pub fn read_kegs(cellar: &Path, formula_name: &str) -> Vec<KegReceipt> {
+        let Ok(entries) = fs::read_dir(cellar.join(formula_name)) else {
+            return Vec::new();
+        };
+
+        let mut kegs: Vec<KegReceipt> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let version = entry.file_name().to_string_lossy().to_string();
+                let receipt = Self::from_path(&entry.path().join("INSTALL_RECEIPT.json")).ok()?;
+                Some(KegReceipt { version, receipt })
+            })
+            .collect();
+
+        kegs.sort_by_key(|keg| keg.receipt.time.unwrap_or(0));
+        kegs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bottle_receipt() {
+        let json = r#"{
+            "homebrew_version": "4.4.0",
+            "used_options": [],
+            "unused_options": [],
+            "built_as_bottle": true,
+            "poured_from_bottle": true,
+            "loaded_from_api": true,
+            "installed_as_dependency": false,
+            "installed_on_request": true,
+            "changed_files": [],
+            "time": 1727000000,
+            "source_modified_time": 1726000000,
+            "compiler": "clang",
+            "aliases": [],
+            "runtime_dependencies": [
+                {
+                    "full_name": "oniguruma",
+                    "version": "6.9.9",
+                    "revision": 0,
+                    "pkg_version": "6.9.9",
+                    "declared_directly": true
+                }
+            ],
+            "source": {
+                "path": "/opt/homebrew/Library/Taps/homebrew/homebrew-core/Formula/j/jq.rb",
+                "tap": "homebrew/core",
+                "tap_git_head": "abc123",
+                "spec": "stable",
+                "versions": {
+                    "stable": "1.7.1",
+                    "head": "HEAD",
+                    "version_scheme": 0
+                }
+            },
+            "arch": "arm64"
+        }"#;
+
+        let receipt = InstallReceipt::parse(json).unwrap();
+        assert_eq!(receipt.time, Some(1727000000));
+        assert!(receipt.poured_from_bottle);
+        assert!(receipt.built_as_bottle);
+        assert!(receipt.used_options.is_empty());
+        assert_eq!(receipt.spec(), Some("stable"));
+        assert_eq!(receipt.runtime_dependency_names(), vec!["oniguruma 6.9.9"]);
+    }
+
+    #[test]
+    fn test_parse_source_build_receipt_with_options() {
+        let json = r#"{
+            "used_options": ["--with-openssl", "--HEAD"],
+            "built_as_bottle": false,
+            "poured_from_bottle": false,
+            "installed_on_request": true,
+            "time": 1600000000,
+            "runtime_dependencies": null,
+            "source": { "spec": "head" }
+        }"#;
+
+        let receipt = InstallReceipt::parse(json).unwrap();
+        assert!(!receipt.poured_from_bottle);
+        assert!(!receipt.built_as_bottle);
+        assert_eq!(receipt.used_options, vec!["--with-openssl", "--HEAD"]);
+        assert_eq!(receipt.spec(), Some("head"));
+        assert!(receipt.runtime_dependency_names().is_empty());
+    }
+
+    #[test]
+    fn test_parse_legacy_receipt_with_missing_fields() {
+        let json = r#"{ "used_options": [], "time": null }"#;
+
+        let receipt = InstallReceipt::parse(json).unwrap();
+        assert_eq!(receipt.time, None);
+        assert!(!receipt.poured_from_bottle);
+        assert_eq!(receipt.spec(), None);
+        assert!(receipt.runtime_dependency_names().is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid_receipt() {
+        assert!(InstallReceipt::parse("not json").is_err());
+    }
+}
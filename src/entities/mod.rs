@@ -1,2 +1,4 @@
 pub mod brew_info_response;
+pub mod install_receipt;
 pub mod package_info;
+pub mod timeline_entry;
@@ -1,19 +1,21 @@
 use crate::entities::brew_info_response::{BrewCask, BrewFormula};
+use crate::entities::install_receipt::{InstallReceipt, KegReceipt};
 use crate::helpers;
 use nestify::nest;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 nest! {
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct PackageInfo {
         pub name: String,
         pub description: String,
-        pub homepage: String,
+        pub homepage: Option<String>,
         pub current_version: String,
         pub installed_version: Option<String>,
         pub package_type:
-            #[derive(Debug, Clone, PartialEq)]
+            #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
             pub enum PackageType {
                 Formulae,
                 Cask,
@@ -23,6 +25,29 @@ nest! {
         pub outdated: bool,
         pub caveats: Option<String>,
         pub installed_at: Option<u64>, // Unix timestamp
+        #[serde(default)]
+        pub deprecated: bool, // Deprecated or disabled in its tap
+        #[serde(default)]
+        pub kegs: Vec<KegReceipt>, // Install receipts of every keg, oldest first (formulae only)
+        #[serde(default)]
+        pub installed_size: Option<u64>, // Bytes on disk of the installed keg (formulae only)
+        #[serde(default)]
+        pub pinned: bool, // Held back from `brew upgrade` (formulae only)
+        #[serde(skip)]
+        pub note: Option<String>, // Personal note from the notes file, never cached
+        #[serde(skip)]
+        pub collections: Vec<String>, // Names of the user's collections the package is in, never cached
+    }
+}
+
+impl PackageType {
+    /// Gets the singular lowercase name of the package type, as used by Homebrew
+    pub fn label(&self) -> &'static str {
+        match self {
+            PackageType::Formulae => "formula",
+            PackageType::Cask => "cask",
+            PackageType::Unknown => "unknown",
+        }
     }
 }
 
@@ -32,7 +57,7 @@ impl PackageInfo {
     pub fn new(
         name: String,
         description: String,
-        homepage: String,
+        homepage: Option<String>,
         current_version: String,
         installed_version: Option<String>,
         package_type: PackageType,
@@ -52,9 +77,24 @@ impl PackageInfo {
             outdated,
             caveats,
             installed_at,
+            deprecated: false,
+            kegs: Vec::new(),
+            installed_size: None,
+            pinned: false,
+            note: None,
+            collections: Vec::new(),
         }
     }
 
+    /// Gets the install receipt of the installed keg, falling back to the newest keg
+    pub fn receipt(&self) -> Option<&InstallReceipt> {
+        self.installed_version
+            .as_ref()
+            .and_then(|version| self.kegs.iter().find(|keg| &keg.version == version))
+            .or_else(|| self.kegs.last())
+            .map(|keg| &keg.receipt)
+    }
+
     /// Checks if the package has an update available
     pub fn has_update_available(&self) -> bool {
         // Trust Homebrew's outdated flag first - it knows about revisions and other subtleties
@@ -107,6 +147,13 @@ impl PackageInfo {
         None
     }
 
+    /// Gets the tap name without the " (cask)" suffix added for display
+    pub fn tap_name(&self) -> Option<&str> {
+        self.tap
+            .as_deref()
+            .map(|tap| tap.strip_suffix(" (cask)").unwrap_or(tap))
+    }
+
     /// Gets the display name with package type prefix
     pub fn get_display_name(&self) -> String {
         match self.package_type {
@@ -132,13 +179,10 @@ impl From<&BrewFormula> for PackageInfo {
             (None, None)
         };
 
-        PackageInfo::new(
+        let mut package = PackageInfo::new(
             formula.name.clone(),
             formula.desc.clone(),
-            formula
-                .homepage
-                .clone()
-                .unwrap_or_else(|| "No homepage available".to_string()),
+            formula.homepage.clone(),
             formula
                 .versions
                 .stable
@@ -150,7 +194,10 @@ impl From<&BrewFormula> for PackageInfo {
             formula.outdated,
             formula.caveats.clone(),
             installed_at,
-        )
+        );
+        package.deprecated = formula.deprecated || formula.disabled;
+        package.pinned = formula.pinned;
+        package
     }
 }
 
@@ -166,21 +213,91 @@ impl From<&BrewCask> for PackageInfo {
             }
         });
 
-        PackageInfo::new(
+        let mut package = PackageInfo::new(
             cask.token.clone(),
             description,
-            cask.homepage
-                .clone()
-                .unwrap_or_else(|| "No homepage available".to_string()),
+            cask.homepage.clone(),
             cask.version.clone(),
             installed_version,
             PackageType::Cask,
             cask.tap.clone().map(|tap| format!("{} (cask)", tap)),
             cask.outdated,
             cask.caveats.clone(),
-            None, // Casks don't have installation timestamp in the JSON
+            cask.installed_time,
+        );
+        package.deprecated = cask.deprecated || cask.disabled;
+        package
+    }
+}
+
+/// Builds packages for tests, starting from an installed and up to date formula
+#[cfg(test)]
+impl PackageInfo {
+    pub fn fixture(name: &str) -> Self {
+        Self::new(
+            name.to_string(),
+            String::new(),
+            None,
+            "1.0.0".to_string(),
+            Some("1.0.0".to_string()),
+            PackageType::Formulae,
+            None,
+            false,
+            None,
+            None,
         )
     }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn with_homepage(mut self, homepage: &str) -> Self {
+        self.homepage = Some(homepage.to_string());
+        self
+    }
+
+    pub fn with_versions(mut self, installed: &str, current: &str) -> Self {
+        self.installed_version = Some(installed.to_string());
+        self.current_version = current.to_string();
+        self
+    }
+
+    pub fn with_type(mut self, package_type: PackageType) -> Self {
+        self.package_type = package_type;
+        self
+    }
+
+    pub fn with_tap(mut self, tap: &str) -> Self {
+        self.tap = Some(tap.to_string());
+        self
+    }
+
+    pub fn with_outdated(mut self, outdated: bool) -> Self {
+        self.outdated = outdated;
+        self
+    }
+
+    pub fn with_deprecated(mut self, deprecated: bool) -> Self {
+        self.deprecated = deprecated;
+        self
+    }
+
+    pub fn with_installed_at(mut self, installed_at: u64) -> Self {
+        self.installed_at = Some(installed_at);
+        self
+    }
+
+    pub fn with_installed_size(mut self, installed_size: u64) -> Self {
+        self.installed_size = Some(installed_size);
+        self
+    }
+
+    pub fn with_kegs(mut self, kegs: Vec<KegReceipt>) -> Self {
+        self.kegs = kegs;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -193,7 +310,7 @@ mod tests {
         let package1 = PackageInfo {
             name: "httpie".to_string(),
             description: "HTTP client".to_string(),
-            homepage: "https://httpie.io".to_string(),
+            homepage: Some("https://httpie.io".to_string()),
             current_version: "3.2.4".to_string(),
             installed_version: Some("3.2.4_4".to_string()),
             package_type: PackageType::Formulae,
@@ -201,6 +318,12 @@ mod tests {
             outdated: false,
             caveats: None,
             installed_at: Some(1696118400), // Example timestamp
+            deprecated: false,
+            kegs: Vec::new(),
+            installed_size: None,
+            pinned: false,
+            note: None,
+            collections: Vec::new(),
         };
         assert!(!package1.has_update_available());
 
@@ -208,7 +331,7 @@ mod tests {
         let package2 = PackageInfo {
             name: "httpie".to_string(),
             description: "HTTP client".to_string(),
-            homepage: "https://httpie.io".to_string(),
+            homepage: Some("https://httpie.io".to_string()),
             current_version: "3.2.5".to_string(),
             installed_version: Some("3.2.4".to_string()),
             package_type: PackageType::Formulae,
@@ -216,6 +339,12 @@ mod tests {
             outdated: false,
             caveats: None,
             installed_at: Some(1696118400), // Example timestamp
+            deprecated: false,
+            kegs: Vec::new(),
+            installed_size: None,
+            pinned: false,
+            note: None,
+            collections: Vec::new(),
         };
         assert!(package2.has_update_available());
 
@@ -223,7 +352,7 @@ mod tests {
         let package3 = PackageInfo {
             name: "somepackage".to_string(),
             description: "Some package".to_string(),
-            homepage: "https://example.com".to_string(),
+            homepage: Some("https://example.com".to_string()),
             current_version: "76.1_2".to_string(),
             installed_version: Some("76.1".to_string()),
             package_type: PackageType::Formulae,
@@ -231,6 +360,12 @@ mod tests {
             outdated: false,
             caveats: None,
             installed_at: Some(1696118400), // Example timestamp
+            deprecated: false,
+            kegs: Vec::new(),
+            installed_size: None,
+            pinned: false,
+            note: None,
+            collections: Vec::new(),
         };
         assert!(package3.has_update_available());
     }
@@ -250,12 +385,15 @@ mod tests {
             },
             installed: vec![],
             outdated: false,
+            deprecated: false,
+            disabled: false,
+            pinned: false,
             caveats: None,
         };
 
         let package_info = PackageInfo::from(&formula);
         assert_eq!(package_info.name, "test-formula");
-        assert_eq!(package_info.homepage, "No homepage available");
+        assert_eq!(package_info.homepage, None);
         assert_eq!(package_info.description, "Test description");
     }
 
@@ -271,13 +409,39 @@ mod tests {
             homepage: None,
             version: "1.0.0".to_string(),
             installed: None,
+            installed_time: None,
             outdated: false,
+            deprecated: false,
+            disabled: false,
             caveats: None,
         };
 
         let package_info = PackageInfo::from(&cask);
         assert_eq!(package_info.name, "test-cask");
-        assert_eq!(package_info.homepage, "No homepage available");
+        assert_eq!(package_info.homepage, None);
         assert_eq!(package_info.description, "Test description");
     }
+
+    #[test]
+    fn test_from_brew_cask_with_installed_time() {
+        use crate::entities::brew_info_response::BrewCask;
+
+        let cask = BrewCask {
+            token: "test-cask".to_string(),
+            tap: Some("homebrew/cask".to_string()),
+            name: vec!["Test Cask".to_string()],
+            desc: None,
+            homepage: Some("https://example.com".to_string()),
+            version: "1.0.0".to_string(),
+            installed: Some("1.0.0".to_string()),
+            installed_time: Some(1727000000),
+            outdated: false,
+            deprecated: false,
+            disabled: false,
+            caveats: None,
+        };
+
+        let package_info = PackageInfo::from(&cask);
+        assert_eq!(package_info.installed_at, Some(1727000000));
+    }
 }
@@ -0,0 +1,98 @@
+use crate::entities::package_info::PackageInfo;
+
+/// Whether a keg was the first install of a package or a later upgrade
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimelineEvent {
+    Installed,
+    Upgraded,
+}
+
+/// A single dated install or upgrade on the timeline screen
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    pub time: u64,
+    pub package_name: String,
+    pub version: String,
+    pub event: TimelineEvent,
+    pub poured_from_bottle: bool,
+}
+
+impl TimelineEntry {
+    /// Builds the timeline from the keg receipts of the given packages, newest first.
+    /// The oldest keg of each package counts as the install, every later keg as an upgrade.
+    pub fn collect(packages: &[PackageInfo]) -> Vec<TimelineEntry> {
+        let mut entries: Vec<TimelineEntry> = packages
+            .iter()
+            .flat_map(|package| {
+                package
+                    .kegs
+                    .iter()
+                    .filter_map(|keg| keg.receipt.time.map(|time| (time, keg)))
+                    .enumerate()
+                    .map(|(index, (time, keg))| TimelineEntry {
+                        time,
+                        package_name: package.name.clone(),
+                        version: keg.version.clone(),
+                        event: if index == 0 {
+                            TimelineEvent::Installed
+                        } else {
+                            TimelineEvent::Upgraded
+                        },
+                        poured_from_bottle: keg.receipt.poured_from_bottle,
+                    })
+            })
+            .collect();
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.time));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::install_receipt::{InstallReceipt, KegReceipt};
+
+    fn keg(version: &str, time: Option<u64>) -> KegReceipt {
+        KegReceipt {
+            version: version.to_string(),
+            receipt: InstallReceipt {
+                time,
+                poured_from_bottle: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_collect_orders_newest_first_and_marks_upgrades() {
+        let packages = vec![
+            PackageInfo::fixture("jq")
+                .with_kegs(vec![keg("1.6", Some(100)), keg("1.7.1", Some(300))]),
+            PackageInfo::fixture("wget").with_kegs(vec![keg("1.21", Some(200))]),
+        ];
+
+        let timeline = TimelineEntry::collect(&packages);
+
+        assert_eq!(timeline.len(), 3);
+        assert_eq!(timeline[0].package_name, "jq");
+        assert_eq!(timeline[0].version, "1.7.1");
+        assert_eq!(timeline[0].event, TimelineEvent::Upgraded);
+        assert_eq!(timeline[1].package_name, "wget");
+        assert_eq!(timeline[1].event, TimelineEvent::Installed);
+        assert_eq!(timeline[2].version, "1.6");
+        assert_eq!(timeline[2].event, TimelineEvent::Installed);
+    }
+
+    #[test]
+    fn test_collect_skips_kegs_without_time() {
+        let packages = vec![
+            PackageInfo::fixture("jq").with_kegs(vec![keg("1.6", None), keg("1.7.1", Some(300))]),
+        ];
+
+        let timeline = TimelineEntry::collect(&packages);
+
+        assert_eq!(timeline.len(), 1);
+        assert_eq!(timeline[0].event, TimelineEvent::Installed);
+    }
+}
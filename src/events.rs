@@ -1,111 +1,299 @@
-use crate::app::App;
+use crate::app::{App, ModalState, Screen};
+use crate::filters::QuickFilters;
 use crate::helpers;
+use crate::keymap::{Action, KeyMode, KeyPress, KeyResolution};
+use crate::line_editor::LineEditor;
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+
+/// Number of items one notch of the mouse wheel scrolls
+const WHEEL_STEP: isize = 3;
 
 /// Handles keyboard events and updates application state accordingly
 pub fn handle_key_event(app: &mut App, key: KeyEvent) -> Result<()> {
-    // Check if we're in a modal state first
-    if app.modal_state != crate::app::ModalState::None {
-        handle_modal_keys(app, key)
-    } else if app.is_searching {
-        handle_search_mode_keys(app, key)
-    } else {
-        handle_normal_mode_keys(app, key)
+    // Movement keys scroll the help overlay when it doesn't fit, and any other key closes it
+    if app.modal_state == ModalState::Help {
+        let press = KeyPress::from_event(&key);
+        let page = app.help_rows as isize;
+        match app
+            .keymap
+            .resolve(KeyMode::Normal, &mut app.pending_keys, press)
+        {
+            KeyResolution::Action(action) if app.help_scrolls() => match action {
+                Action::MoveDown => app.scroll_help(1),
+                Action::MoveUp => app.scroll_help(-1),
+                Action::PageDown => app.scroll_help(page),
+                Action::PageUp => app.scroll_help(-page),
+                Action::First => app.scroll_help(isize::MIN),
+                Action::Last => app.scroll_help(isize::MAX),
+                _ => app.close_help(),
+            },
+            KeyResolution::Pending => {}
+            _ => app.close_help(),
+        }
+        return Ok(());
+    }
+
+    let mode = current_key_mode(app);
+    match app
+        .keymap
+        .resolve(mode, &mut app.pending_keys, KeyPress::from_event(&key))
+    {
+        KeyResolution::Action(action) => handle_action(app, mode, action),
+        KeyResolution::Pending => Ok(()),
+        KeyResolution::Unbound => {
+            // Unbound printable keys are typed into the focused text input
+            if mode.takes_text()
+                && let KeyCode::Char(c) = key.code
+                && !c.is_control()
+                && !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            {
+                app.edit_input(|search| search.insert(c));
+            }
+            Ok(())
+        }
     }
 }
 
-/// Handles key events when a modal is open
-fn handle_modal_keys(app: &mut App, key: KeyEvent) -> Result<()> {
-    // Handle 'g' key globally - opens GitHub issues
-    if let KeyCode::Char('g') = key.code {
-        let _ = helpers::open_github_issues();
-        return Ok(());
+/// Handles text pasted into the terminal, which only text inputs accept
+pub fn handle_paste(app: &mut App, text: &str) {
+    if current_key_mode(app).takes_text() {
+        app.edit_input(|search| search.insert_str(text));
     }
+}
+
+/// Handles mouse events, hit-testing against the areas recorded by the last render
+pub fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> Result<()> {
+    let position = Position::new(mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => handle_click(app, position),
+        MouseEventKind::ScrollDown => {
+            scroll(app, WHEEL_STEP);
+            Ok(())
+        }
+        MouseEventKind::ScrollUp => {
+            scroll(app, -WHEEL_STEP);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Handles a left click on a modal button, a list item or the homepage link
+fn handle_click(app: &mut App, position: Position) -> Result<()> {
+    app.pending_keys.clear();
 
     match app.modal_state {
-        crate::app::ModalState::UpdateProgress => {
-            // During update progress, no keys are allowed - user must wait for completion
-            // The modal will automatically close when the update finishes
-            match key.code {
-                KeyCode::Char('q') => {
-                    // Allow quitting the entire application even during update
-                    app.quit();
-                }
-                _ => {
-                    // Ignore all other keys during update
-                }
+        ModalState::None => {}
+        // Any click closes the help overlay
+        ModalState::Help => {
+            app.close_help();
+            return Ok(());
+        }
+        _ => {
+            let button = app
+                .mouse_targets
+                .buttons
+                .iter()
+                .find(|(area, _)| area.contains(position))
+                .map(|(_, action)| *action);
+            if let Some(action) = button {
+                handle_action(app, current_key_mode(app), action)?;
             }
+            // Clicking a picker row chooses it
+            let row = app
+                .mouse_targets
+                .rows
+                .iter()
+                .find(|(area, _)| area.contains(position))
+                .map(|(_, row)| *row);
+            if let Some(row) = row {
+                app.select_picker_row(row);
+                handle_action(app, KeyMode::Picker, Action::Confirm)?;
+            }
+            return Ok(());
         }
-        crate::app::ModalState::UninstallConfirmation => {
-            // Handle uninstall confirmation dialog
-            match key.code {
-                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
-                    app.confirm_uninstall();
-                }
-                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                    app.cancel_uninstall();
-                }
-                KeyCode::Char('q') => {
-                    // Allow quitting the entire application
-                    app.quit();
-                }
-                _ => {
-                    // Ignore other keys
-                }
+    }
+
+    if let Some(list) = app.mouse_targets.list
+        && list.contains(position)
+    {
+        let row = (position.y - list.y) as usize;
+        if app.screen == Screen::Timeline {
+            let index = app.timeline_state.offset() + row;
+            if index < app.timeline.len() {
+                app.timeline_state.select(Some(index));
+            }
+        } else if app.screen == Screen::History {
+            let index = app.operations_state.offset() + row;
+            if index < app.shown_operations().len() {
+                app.operations_state.select(Some(index));
             }
+        } else {
+            app.select_index(app.list_state.offset() + row);
         }
-        crate::app::ModalState::None => {
-            // This shouldn't happen, but handle gracefully
+        return Ok(());
+    }
+
+    let column = app
+        .mouse_targets
+        .columns
+        .iter()
+        .position(|area| area.contains(position));
+    if let Some(column) = column {
+        let row = (position.y - app.mouse_targets.columns[column].y) as usize;
+        if row < app.rows_per_column {
+            app.select_index((app.column_scroll_offset + column) * app.rows_per_column + row);
         }
+        return Ok(());
+    }
+
+    if app
+        .mouse_targets
+        .homepage
+        .is_some_and(|homepage| homepage.contains(position))
+    {
+        app.open_selected_homepage();
     }
     Ok(())
 }
 
-/// Handles key events in normal navigation mode
-fn handle_normal_mode_keys(app: &mut App, key: KeyEvent) -> Result<()> {
-    match key.code {
-        KeyCode::Char('g') => {
-            let _ = helpers::open_github_issues();
+/// Moves the selection of the current screen with the mouse wheel
+fn scroll(app: &mut App, delta: isize) {
+    if app.modal_state == ModalState::Help {
+        app.scroll_help(delta);
+        return;
+    }
+    if matches!(app.modal_state, ModalState::Picker(_)) {
+        app.move_picker_selection(delta);
+        return;
+    }
+    if app.modal_state != ModalState::None {
+        return;
+    }
+    if app.screen == Screen::Timeline {
+        app.move_timeline_selection(delta);
+    } else if app.screen == Screen::History {
+        app.move_history_selection(delta);
+    } else {
+        app.scroll_selection(delta);
+    }
+}
+
+/// Gets the mode whose bindings apply to the next key press
+fn current_key_mode(app: &App) -> KeyMode {
+    match app.modal_state {
+        ModalState::UpdateProgress => KeyMode::Progress,
+        ModalState::UninstallConfirmation => KeyMode::Confirm,
+        ModalState::Prompt(_) => KeyMode::Prompt,
+        ModalState::Picker(_) => KeyMode::Picker,
+        ModalState::None | ModalState::Help => {
+            if app.command_line.is_some() {
+                KeyMode::Command
+            } else if app.screen == Screen::Timeline {
+                KeyMode::Timeline
+            } else if app.screen == Screen::History {
+                KeyMode::History
+            } else if app.is_searching {
+                KeyMode::Search
+            } else {
+                KeyMode::Normal
+            }
         }
-        KeyCode::Char('q') => app.quit(),
-        KeyCode::Down | KeyCode::Char('j') => app.next(),
-        KeyCode::Up | KeyCode::Char('k') => app.previous(),
-        KeyCode::Left | KeyCode::Char('h') => app.move_left(),
-        KeyCode::Right | KeyCode::Char('l') => app.move_right(),
-        KeyCode::PageDown => app.page_down(),
-        KeyCode::PageUp => app.page_up(),
-        KeyCode::Home => app.first(),
-        KeyCode::End => app.go_to_last(),
-        KeyCode::Char('/') => app.start_search(),
-        KeyCode::Char('x') => app.uninstall_selected_package()?,
-        KeyCode::Char('u') => app.update_selected_package()?,
-        KeyCode::Char('r') => app.refresh_packages()?,
-        _ => {}
     }
-    Ok(())
 }
 
-/// Handles key events in search mode
-fn handle_search_mode_keys(app: &mut App, key: KeyEvent) -> Result<()> {
-    match key.code {
-        KeyCode::Char('g') => {
-            // Open GitHub issues page (global hotkey)
+/// Runs a bound action. The keymap only binds actions a mode supports.
+fn handle_action(app: &mut App, mode: KeyMode, action: Action) -> Result<()> {
+    match (mode, action) {
+        (_, Action::OpenIssues) => {
             let _ = helpers::open_github_issues();
         }
-        KeyCode::Esc => app.end_search(),
-        KeyCode::Enter => app.end_search(),
-        KeyCode::Backspace => app.remove_search_char(),
-        KeyCode::Down | KeyCode::Char('j') => app.next(),
-        KeyCode::Up | KeyCode::Char('k') => app.previous(),
-        KeyCode::Left | KeyCode::Char('h') => app.move_left(),
-        KeyCode::Right | KeyCode::Char('l') => app.move_right(),
-        KeyCode::PageDown => app.page_down(),
-        KeyCode::PageUp => app.page_up(),
-        KeyCode::Home => app.first(),
-        KeyCode::End => app.go_to_last(),
-        KeyCode::Char(c) if c.is_ascii() && !c.is_control() => app.add_search_char(c),
-        _ => {}
+        (_, Action::Quit) => app.quit(),
+        (_, Action::Help) => app.show_help(mode),
+        // During update progress only quitting is allowed.
+        // The modal will automatically close when the update finishes.
+        (KeyMode::Progress, _) => {}
+        (KeyMode::Confirm, Action::Confirm) => app.confirm_uninstall(),
+        (KeyMode::Confirm, Action::Cancel) => app.cancel_uninstall(),
+        (KeyMode::Prompt, Action::Confirm) => app.submit_prompt(),
+        (KeyMode::Picker, Action::Confirm) => match app.take_picked_command() {
+            Some(action) => handle_action(app, KeyMode::Normal, action)?,
+            None => app.choose_picked(),
+        },
+        (KeyMode::Picker, Action::MoveDown) => app.move_picker_selection(1),
+        (KeyMode::Picker, Action::MoveUp) => app.move_picker_selection(-1),
+        (KeyMode::Prompt | KeyMode::Picker, Action::Cancel) => app.cancel_modal(),
+        (KeyMode::Command, Action::Confirm) => app.submit_command_line(),
+        (KeyMode::Command, Action::Cancel) => app.close_command_line(),
+        (KeyMode::Timeline, Action::MoveDown) => app.move_timeline_selection(1),
+        (KeyMode::Timeline, Action::MoveUp) => app.move_timeline_selection(-1),
+        (KeyMode::Timeline, Action::PageDown) => app.move_timeline_selection(10),
+        (KeyMode::Timeline, Action::PageUp) => app.move_timeline_selection(-10),
+        (KeyMode::Timeline, Action::First) => app.first_timeline_entry(),
+        (KeyMode::Timeline, Action::Last) => app.last_timeline_entry(),
+        (KeyMode::Timeline, Action::OpenEntry) => app.open_selected_timeline_entry(),
+        (KeyMode::History, Action::MoveDown) => app.move_history_selection(1),
+        (KeyMode::History, Action::MoveUp) => app.move_history_selection(-1),
+        (KeyMode::History, Action::PageDown) => app.move_history_selection(10),
+        (KeyMode::History, Action::PageUp) => app.move_history_selection(-10),
+        (KeyMode::History, Action::First) => app.move_history_selection(isize::MIN),
+        (KeyMode::History, Action::Last) => app.move_history_selection(isize::MAX),
+        (KeyMode::History, Action::OpenEntry) => app.open_selected_history_entry(),
+        (KeyMode::History, Action::Undo) => app.undo_selected_uninstall(),
+        (_, Action::Undo) => app.undo_last_uninstall(),
+        (_, Action::FilterPackage) => app.toggle_history_filter(),
+        (_, Action::Timeline) => app.toggle_timeline(),
+        (_, Action::History) => app.toggle_history(),
+        (_, Action::MoveDown) => app.next(),
+        (_, Action::MoveUp) => app.previous(),
+        (_, Action::MoveLeft) => app.move_left(),
+        (_, Action::MoveRight) => app.move_right(),
+        (_, Action::PageDown) => app.page_down(),
+        (_, Action::PageUp) => app.page_up(),
+        (_, Action::First) => app.first(),
+        (_, Action::Last) => app.go_to_last(),
+        (_, Action::Search) => app.start_search(),
+        (_, Action::EndSearch) => app.end_search(),
+        (_, Action::SubmitSearch) => app.submit_search(),
+        (_, Action::HistoryPrevious) => app.recall_history(true),
+        (_, Action::HistoryNext) => app.recall_history(false),
+        (_, Action::SaveSearch) => app.prompt_search_name(),
+        (_, Action::SavedSearches) => app.show_saved_searches(),
+        (_, Action::RemoveEntry) => app.remove_picked(),
+        (_, Action::Complete) => app.complete_command(),
+        (_, Action::CommandLine) => app.open_command_line(),
+        (_, Action::DeleteChar) => app.edit_input(LineEditor::delete_before),
+        (_, Action::DeleteNextChar) => app.edit_input(LineEditor::delete_after),
+        (_, Action::DeleteWord) => app.edit_input(LineEditor::delete_word_before),
+        (_, Action::DeleteToStart) => app.edit_input(LineEditor::delete_to_start),
+        (_, Action::CursorLeft) => app.edit_input(LineEditor::move_left),
+        (_, Action::CursorRight) => app.edit_input(LineEditor::move_right),
+        (_, Action::CursorStart) => app.edit_input(LineEditor::move_to_start),
+        (_, Action::CursorEnd) => app.edit_input(LineEditor::move_to_end),
+        (_, Action::Uninstall) => app.uninstall_selected_package()?,
+        (_, Action::Update) => app.update_selected_package()?,
+        (_, Action::EditNote) => app.prompt_package_note(),
+        (_, Action::TogglePin) => app.toggle_selected_pin(),
+        (_, Action::OpenHomepage) => app.open_selected_homepage(),
+        (_, Action::Export) => app.prompt_export_path(),
+        (_, Action::CommandPalette) => app.show_command_palette(),
+        (_, Action::EditCollections) => app.show_collections(),
+        (_, Action::UpdateCollection) => app.update_collection(),
+        (_, Action::UninstallCollection) => app.uninstall_collection(),
+        (_, Action::Refresh) => app.refresh_in_background(),
+        (_, Action::CycleSort) => app.cycle_sort_mode(),
+        (_, Action::FilterFormulae) => app.update_filters(QuickFilters::toggle_formulae_only),
+        (_, Action::FilterCasks) => app.update_filters(QuickFilters::toggle_casks_only),
+        (_, Action::FilterOutdated) => app.update_filters(|f| f.outdated_only = !f.outdated_only),
+        (_, Action::FilterPinned) => app.update_filters(|f| f.pinned_only = !f.pinned_only),
+        (_, Action::FilterTap) => app.cycle_tap_filter(),
+        (_, Action::FilterCollection) => app.cycle_collection_filter(),
+        (_, Action::ClearFilters) => app.set_filters(QuickFilters::default()),
+        (_, Action::OpenEntry | Action::Confirm | Action::Cancel) => {}
     }
     Ok(())
 }
@@ -0,0 +1,323 @@
+use crate::entities::package_info::{PackageInfo, PackageType};
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Version of the JSON inventory format, bumped whenever a field is removed or changes meaning
+pub const INVENTORY_FORMAT_VERSION: u32 = 1;
+
+/// Taps that Homebrew always has, which don't need a `tap` line in a Brewfile
+const DEFAULT_TAPS: [&str; 2] = ["homebrew/core", "homebrew/cask"];
+
+/// Output formats supported by the `export` command
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    Brewfile,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Picks the format from a file's extension, defaulting to a Brewfile
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("json") => ExportFormat::Json,
+            Some("csv") => ExportFormat::Csv,
+            Some("md" | "markdown") => ExportFormat::Markdown,
+            _ => ExportFormat::Brewfile,
+        }
+    }
+}
+
+/// One package in an exported inventory. The field names and their order are a stable,
+/// documented format that scripts can rely on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InventoryRecord {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub package_type: String, // "formula" or "cask"
+    pub tap: Option<String>,
+    pub installed_version: Option<String>,
+    pub current_version: String,
+    pub outdated: bool,
+    pub installed_at: Option<u64>, // Unix timestamp
+    pub homepage: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>, // Personal note about the package
+}
+
+/// The JSON inventory document
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Inventory {
+    pub format_version: u32,
+    pub packages: Vec<InventoryRecord>,
+}
+
+impl From<&PackageInfo> for InventoryRecord {
+    fn from(package: &PackageInfo) -> Self {
+        Self {
+            name: package.name.clone(),
+            package_type: package.package_type.label().to_string(),
+            tap: package.tap_name().map(str::to_string),
+            installed_version: package.installed_version.clone(),
+            current_version: package.current_version.clone(),
+            outdated: package.has_update_available(),
+            installed_at: package.installed_at,
+            homepage: package.homepage.clone(),
+            note: package.note.clone(),
+        }
+    }
+}
+
+/// Parses a JSON inventory, such as one exported on another machine
+pub fn parse_inventory(contents: &str) -> Result<Inventory> {
+    let inventory: Inventory = serde_json::from_str(contents)
+        .map_err(|e| anyhow::anyhow!("Not a JSON inventory: {}", e))?;
+    if inventory.format_version > INVENTORY_FORMAT_VERSION {
+        anyhow::bail!(
+            "Inventory format version {} is newer than the supported version {}",
+            inventory.format_version,
+            INVENTORY_FORMAT_VERSION
+        );
+    }
+    Ok(inventory)
+}
+
+/// Formats packages in the requested export format
+pub fn format_packages(packages: &[PackageInfo], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Brewfile => Ok(to_brewfile(packages)),
+        ExportFormat::Json => to_json(packages),
+        ExportFormat::Csv => to_csv(packages),
+        ExportFormat::Markdown => Ok(to_markdown(packages)),
+    }
+}
+
+/// Formats packages as a Brewfile that `brew bundle` can install from.
+/// Notes become comments at the end of their package's line.
+pub fn to_brewfile(packages: &[PackageInfo]) -> String {
+    let taps: BTreeSet<&str> = packages
+        .iter()
+        .filter_map(|pkg| pkg.tap_name())
+        .filter(|tap| !DEFAULT_TAPS.contains(tap))
+        .collect();
+
+    let mut output = String::new();
+    for tap in taps {
+        output.push_str(&format!("tap \"{}\"\n", tap));
+    }
+    for pkg in packages
+        .iter()
+        .filter(|pkg| pkg.package_type == PackageType::Formulae)
+    {
+        output.push_str(&format!("brew \"{}\"{}\n", pkg.name, brewfile_comment(pkg)));
+    }
+    for pkg in packages
+        .iter()
+        .filter(|pkg| pkg.package_type == PackageType::Cask)
+    {
+        output.push_str(&format!("cask \"{}\"{}\n", pkg.name, brewfile_comment(pkg)));
+    }
+    output
+}
+
+/// Gets the Brewfile comment carrying a package's note, kept on one line
+fn brewfile_comment(package: &PackageInfo) -> String {
+    match &package.note {
+        Some(note) => format!(" # {}", note.replace(['\r', '\n'], " ")),
+        None => String::new(),
+    }
+}
+
+/// Formats packages as a pretty-printed JSON inventory document
+pub fn to_json(packages: &[PackageInfo]) -> Result<String> {
+    let inventory = Inventory {
+        format_version: INVENTORY_FORMAT_VERSION,
+        packages: packages.iter().map(InventoryRecord::from).collect(),
+    };
+    Ok(serde_json::to_string_pretty(&inventory)? + "\n")
+}
+
+/// Formats packages as CSV with a header row. Missing values are left empty.
+pub fn to_csv(packages: &[PackageInfo]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for package in packages {
+        writer.serialize(InventoryRecord::from(package))?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Formats packages as a Markdown table
+pub fn to_markdown(packages: &[PackageInfo]) -> String {
+    let mut output = String::from(
+        "| Name | Type | Tap | Installed Version | Current Version | Outdated | Installed At | Homepage | Note |\n\
+         |------|------|-----|-------------------|-----------------|----------|--------------|----------|------|\n",
+    );
+
+    for record in packages.iter().map(InventoryRecord::from) {
+        let cells = [
+            record.name,
+            record.package_type,
+            record.tap.unwrap_or_default(),
+            record.installed_version.unwrap_or_default(),
+            record.current_version,
+            if record.outdated { "yes" } else { "no" }.to_string(),
+            record
+                .installed_at
+                .map(|timestamp| format!("{} UTC", crate::helpers::format_date(timestamp)))
+                .unwrap_or_default(),
+            record.homepage.unwrap_or_default(),
+            record.note.unwrap_or_default(),
+        ];
+        let cells: Vec<String> = cells
+            .iter()
+            .map(|cell| cell.replace('|', "\\|").replace(['\r', '\n'], " "))
+            .collect();
+        output.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gets an installed package with every exported field filled in
+    fn package(name: &str) -> PackageInfo {
+        PackageInfo::fixture(name)
+            .with_description("Description")
+            .with_homepage(&format!("https://example.com/{}", name))
+            .with_installed_at(1727000000)
+    }
+
+    fn sample_packages() -> Vec<PackageInfo> {
+        vec![
+            package("jq")
+                .with_tap("homebrew/core")
+                .with_versions("1.7.1", "1.7.1"),
+            PackageInfo {
+                note: Some("Work profile | testing".to_string()),
+                ..package("firefox")
+                    .with_type(PackageType::Cask)
+                    .with_tap("homebrew/cask (cask)")
+                    .with_versions("130.0", "131.0")
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        let format = |path: &str| ExportFormat::from_path(Path::new(path));
+        assert_eq!(format("~/Brewfile"), ExportFormat::Brewfile);
+        assert_eq!(format("packages.JSON"), ExportFormat::Json);
+        assert_eq!(format("packages.csv"), ExportFormat::Csv);
+        assert_eq!(format("README.md"), ExportFormat::Markdown);
+    }
+
+    #[test]
+    fn test_to_brewfile() {
+        let mut packages = sample_packages();
+        packages.push(
+            package("brew-explorer")
+                .with_tap("cosmincatalin/tap")
+                .with_versions("0.1.5", "0.1.5"),
+        );
+
+        assert_eq!(
+            to_brewfile(&packages),
+            "tap \"cosmincatalin/tap\"\nbrew \"jq\"\nbrew \"brew-explorer\"\ncask \"firefox\" # Work profile | testing\n"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = to_json(&sample_packages()).unwrap();
+        let inventory: Inventory = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(inventory.format_version, INVENTORY_FORMAT_VERSION);
+        assert_eq!(
+            inventory.packages[1],
+            InventoryRecord {
+                name: "firefox".to_string(),
+                package_type: "cask".to_string(),
+                tap: Some("homebrew/cask".to_string()),
+                installed_version: Some("130.0".to_string()),
+                current_version: "131.0".to_string(),
+                outdated: true,
+                installed_at: Some(1727000000),
+                homepage: Some("https://example.com/firefox".to_string()),
+                note: Some("Work profile | testing".to_string()),
+            }
+        );
+        assert!(json.contains("\"type\": \"cask\""));
+    }
+
+    #[test]
+    fn test_package_without_homepage() {
+        let packages = vec![PackageInfo {
+            homepage: None,
+            ..package("jq").with_versions("1.7.1", "1.7.1")
+        }];
+
+        let inventory = parse_inventory(&to_json(&packages).unwrap()).unwrap();
+        assert_eq!(inventory.packages[0].homepage, None);
+        assert!(to_json(&packages).unwrap().contains("\"homepage\": null"));
+        assert_eq!(
+            to_csv(&packages).unwrap().lines().nth(1),
+            Some("jq,formula,,1.7.1,1.7.1,false,1727000000,,")
+        );
+        assert!(to_markdown(&packages).ends_with("| 2024-09-22 10:13 UTC |  |  |\n"));
+    }
+
+    #[test]
+    fn test_parse_inventory() {
+        let json = to_json(&sample_packages()).unwrap();
+        let inventory = parse_inventory(&json).unwrap();
+        assert_eq!(inventory.packages.len(), 2);
+        assert_eq!(inventory.packages[0].name, "jq");
+
+        // Fields added in later versions of the same format are ignored
+        let inventory =
+            parse_inventory(r#"{"format_version":1,"packages":[],"machine":"laptop"}"#).unwrap();
+        assert!(inventory.packages.is_empty());
+
+        assert!(parse_inventory(r#"{"format_version":2,"packages":[]}"#).is_err());
+        assert!(parse_inventory("brew \"jq\"").is_err());
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = to_csv(&sample_packages()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "name,type,tap,installed_version,current_version,outdated,installed_at,homepage,note"
+        );
+        assert_eq!(
+            lines[1],
+            "jq,formula,homebrew/core,1.7.1,1.7.1,false,1727000000,https://example.com/jq,"
+        );
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let markdown = to_markdown(&sample_packages());
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[3],
+            "| firefox | cask | homebrew/cask | 130.0 | 131.0 | yes | 2024-09-22 10:13 UTC | https://example.com/firefox | Work profile \\| testing |"
+        );
+    }
+}
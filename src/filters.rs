@@ -0,0 +1,222 @@
+use crate::entities::package_info::{PackageInfo, PackageType};
+use serde::{Deserialize, Serialize};
+
+/// Filters that narrow the package list independently of the search query
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuickFilters {
+    pub formulae_only: bool,
+    pub casks_only: bool,
+    pub outdated_only: bool,
+    pub pinned_only: bool,
+    pub tap: Option<String>,        // Only packages from this tap
+    pub collection: Option<String>, // Only packages in this collection
+}
+
+impl QuickFilters {
+    /// Returns true if any filter is switched on
+    pub fn is_active(&self) -> bool {
+        self.formulae_only
+            || self.casks_only
+            || self.outdated_only
+            || self.pinned_only
+            || self.tap.is_some()
+            || self.collection.is_some()
+    }
+
+    /// Checks whether a package passes every active filter
+    pub fn matches(&self, package: &PackageInfo) -> bool {
+        if self.formulae_only && package.package_type != PackageType::Formulae {
+            return false;
+        }
+        if self.casks_only && package.package_type != PackageType::Cask {
+            return false;
+        }
+        if self.outdated_only && !package.has_update_available() {
+            return false;
+        }
+        if self.pinned_only && !package.pinned {
+            return false;
+        }
+        if let Some(tap) = &self.tap
+            && package.tap_name() != Some(tap.as_str())
+        {
+            return false;
+        }
+        if let Some(collection) = &self.collection
+            && !package.collections.contains(collection)
+        {
+            return false;
+        }
+        true
+    }
+
+    /// Toggles the formulae filter, switching off the casks filter that would exclude everything
+    pub fn toggle_formulae_only(&mut self) {
+        self.formulae_only = !self.formulae_only;
+        if self.formulae_only {
+            self.casks_only = false;
+        }
+    }
+
+    /// Toggles the casks filter, switching off the formulae filter that would exclude everything
+    pub fn toggle_casks_only(&mut self) {
+        self.casks_only = !self.casks_only;
+        if self.casks_only {
+            self.formulae_only = false;
+        }
+    }
+
+    /// Moves the tap filter to the next of the given sorted taps, and back to none after the last
+    pub fn cycle_tap(&mut self, taps: &[String]) {
+        self.tap = next_choice(&self.tap, taps);
+    }
+
+    /// Moves the collection filter to the next of the given sorted collections, and back to none
+    /// after the last
+    pub fn cycle_collection(&mut self, collections: &[String]) {
+        self.collection = next_choice(&self.collection, collections);
+    }
+
+    /// Gets short labels for the active filters, for display in the list title
+    pub fn labels(&self) -> Vec<String> {
+        let mut labels = Vec::new();
+        if self.formulae_only {
+            labels.push("formulae".to_string());
+        }
+        if self.casks_only {
+            labels.push("casks".to_string());
+        }
+        if self.outdated_only {
+            labels.push("outdated".to_string());
+        }
+        if self.pinned_only {
+            labels.push("pinned".to_string());
+        }
+        if let Some(tap) = &self.tap {
+            labels.push(format!("tap:{}", tap));
+        }
+        if let Some(collection) = &self.collection {
+            labels.push(format!("collection:{}", collection));
+        }
+        labels
+    }
+}
+
+/// Gets the choice after the current one, the first if there is none, and none after the last
+fn next_choice(current: &Option<String>, choices: &[String]) -> Option<String> {
+    match current {
+        None => choices.first().cloned(),
+        Some(current) => choices
+            .iter()
+            .position(|choice| choice == current)
+            .and_then(|index| choices.get(index + 1))
+            .cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_filters_match_everything() {
+        let filters = QuickFilters::default();
+        assert!(!filters.is_active());
+        assert!(filters.matches(&PackageInfo::fixture("jq")));
+        assert!(
+            filters.matches(
+                &PackageInfo::fixture("firefox")
+                    .with_type(PackageType::Cask)
+                    .with_outdated(true)
+            )
+        );
+        assert!(filters.labels().is_empty());
+    }
+
+    #[test]
+    fn test_combined_filters() {
+        let filters = QuickFilters {
+            casks_only: true,
+            outdated_only: true,
+            ..Default::default()
+        };
+
+        assert!(filters.is_active());
+        assert!(
+            filters.matches(
+                &PackageInfo::fixture("firefox")
+                    .with_type(PackageType::Cask)
+                    .with_outdated(true)
+            )
+        );
+        assert!(!filters.matches(&PackageInfo::fixture("iterm2").with_type(PackageType::Cask)));
+        assert!(!filters.matches(&PackageInfo::fixture("jq").with_outdated(true)));
+        assert_eq!(filters.labels(), vec!["casks", "outdated"]);
+    }
+
+    #[test]
+    fn test_pinned_and_tap_filters() {
+        let mut pinned = PackageInfo::fixture("postgresql@14").with_outdated(true);
+        pinned.pinned = true;
+        pinned.tap = Some("homebrew/core".to_string());
+        let mut cask = PackageInfo::fixture("firefox").with_type(PackageType::Cask);
+        cask.tap = Some("homebrew/cask (cask)".to_string());
+
+        let filters = QuickFilters {
+            pinned_only: true,
+            ..Default::default()
+        };
+        assert!(filters.matches(&pinned));
+        assert!(!filters.matches(&cask));
+
+        let filters = QuickFilters {
+            tap: Some("homebrew/cask".to_string()),
+            ..Default::default()
+        };
+        assert!(!filters.matches(&pinned));
+        assert!(filters.matches(&cask));
+        assert_eq!(filters.labels(), vec!["tap:homebrew/cask"]);
+    }
+
+    #[test]
+    fn test_type_filters_exclude_each_other() {
+        let mut filters = QuickFilters::default();
+        filters.toggle_formulae_only();
+        filters.toggle_casks_only();
+        assert!(filters.casks_only);
+        assert!(!filters.formulae_only);
+
+        filters.toggle_casks_only();
+        assert!(!filters.is_active());
+    }
+
+    #[test]
+    fn test_cycle_tap() {
+        let taps = vec!["homebrew/cask".to_string(), "homebrew/core".to_string()];
+        let mut filters = QuickFilters::default();
+
+        filters.cycle_tap(&taps);
+        assert_eq!(filters.tap.as_deref(), Some("homebrew/cask"));
+        filters.cycle_tap(&taps);
+        assert_eq!(filters.tap.as_deref(), Some("homebrew/core"));
+        filters.cycle_tap(&taps);
+        assert_eq!(filters.tap, None);
+    }
+
+    #[test]
+    fn test_collection_filter() {
+        let mut helm = PackageInfo::fixture("helm");
+        helm.collections = vec!["work".to_string()];
+        let jq = PackageInfo::fixture("jq");
+
+        let mut filters = QuickFilters::default();
+        filters.cycle_collection(&["work".to_string()]);
+        assert!(filters.matches(&helm));
+        assert!(!filters.matches(&jq));
+        assert_eq!(filters.labels(), vec!["collection:work"]);
+
+        filters.cycle_collection(&["work".to_string()]);
+        assert!(!filters.is_active());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::entities::package_info::PackageInfo;
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 8;
+const WORD_START_BONUS: i64 = 8;
+
+/// How a package matched a search query. Tiers are ordered best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchTier {
+    ExactName,
+    NamePrefix,
+    NameSubstring,
+    NameFuzzy,
+    Description,
+    Note,
+}
+
+/// A package that matched a search query, with the characters to highlight
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageMatch {
+    pub tier: MatchTier,
+    pub score: i64,                        // Higher is better within a tier
+    pub name_positions: Vec<usize>,        // Indices of the matched characters of the name
+    pub description_positions: Vec<usize>, // Indices of the matched characters of the description
+    pub note_positions: Vec<usize>,        // Indices of the matched characters of the note
+}
+
+/// Matches a package against a query, case-insensitively.
+/// The name is matched fuzzily; the description and note only on the whole query, since short
+/// queries are a subsequence of almost every description.
+pub fn match_package(package: &PackageInfo, query: &str) -> Option<PackageMatch> {
+    let query = fold(query.trim());
+    if query.is_empty() {
+        return None;
+    }
+
+    let name = fold(&package.name);
+    let description = fold(&package.description);
+    let description_positions = find_substring(&description, &query).unwrap_or_default();
+    let note = fold(package.note.as_deref().unwrap_or_default());
+    let note_positions = find_substring(&note, &query).unwrap_or_default();
+
+    let (tier, score, name_positions) = match match_name(&name, &query) {
+        Some((tier, positions)) => (tier, score(&name, &positions), positions),
+        None if !description_positions.is_empty() => (
+            MatchTier::Description,
+            score(&description, &description_positions),
+            Vec::new(),
+        ),
+        None if !note_positions.is_empty() => {
+            (MatchTier::Note, score(&note, &note_positions), Vec::new())
+        }
+        None => return None,
+    };
+    Some(PackageMatch {
+        tier,
+        score,
+        name_positions,
+        description_positions,
+        note_positions,
+    })
+}
+
+/// Matches one line of text against a query the way package names are matched.
+/// Returns the tier, the score and the indices of the matched characters.
+pub fn match_text(text: &str, query: &str) -> Option<(MatchTier, i64, Vec<usize>)> {
+    let query = fold(query.trim());
+    if query.is_empty() {
+        return None;
+    }
+    let text = fold(text);
+    match_name(&text, &query).map(|(tier, positions)| (tier, score(&text, &positions), positions))
+}
+
+/// Keeps the packages matching the query, best matches first.
+/// Packages that match equally well keep their order, so the chosen sort mode still applies to them.
+pub fn rank<'a>(
+    packages: impl IntoIterator<Item = &'a PackageInfo>,
+    query: &str,
+) -> Vec<&'a PackageInfo> {
+    if query.trim().is_empty() {
+        return packages.into_iter().collect();
+    }
+
+    let mut ranked: Vec<(PackageMatch, &PackageInfo)> = packages
+        .into_iter()
+        .filter_map(|pkg| match_package(pkg, query).map(|found| (found, pkg)))
+        .collect();
+    ranked.sort_by(|(a, _), (b, _)| a.tier.cmp(&b.tier).then(b.score.cmp(&a.score)));
+    ranked.into_iter().map(|(_, pkg)| pkg).collect()
+}
+
+/// Lowercases text one character at a time, so character indices stay the same
+fn fold(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+/// Matches a lowercased name: exactly, by prefix, as a substring or fuzzily
+fn match_name(name: &[char], query: &[char]) -> Option<(MatchTier, Vec<usize>)> {
+    if name == query {
+        Some((MatchTier::ExactName, substring_positions(0, query.len())))
+    } else if name.starts_with(query) {
+        Some((MatchTier::NamePrefix, substring_positions(0, query.len())))
+    } else if let Some(positions) = find_substring(name, query) {
+        Some((MatchTier::NameSubstring, positions))
+    } else {
+        fuzzy_positions(name, query).map(|positions| (MatchTier::NameFuzzy, positions))
+    }
+}
+
+/// Gets the indices of a run of characters
+fn substring_positions(start: usize, len: usize) -> Vec<usize> {
+    (start..start + len).collect()
+}
+
+/// Finds the first occurrence of the query in the text
+fn find_substring(text: &[char], query: &[char]) -> Option<Vec<usize>> {
+    text.windows(query.len())
+        .position(|window| window == query)
+        .map(|start| substring_positions(start, query.len()))
+}
+
+/// Finds the query as a subsequence of the text, trying every start and keeping the best scoring
+fn fuzzy_positions(text: &[char], query: &[char]) -> Option<Vec<usize>> {
+    text.iter()
+        .enumerate()
+        .filter(|(_, c)| **c == query[0])
+        .filter_map(|(start, _)| {
+            let mut positions = vec![start];
+            let mut next = start + 1;
+            for wanted in &query[1..] {
+                let offset = text[next..].iter().position(|c| c == wanted)?;
+                positions.push(next + offset);
+                next += offset + 1;
+            }
+            Some(positions)
+        })
+        .max_by_key(|positions| score(text, positions))
+}
+
+/// Scores matched characters, rewarding runs and word starts and penalising gaps
+fn score(text: &[char], positions: &[usize]) -> i64 {
+    let mut total = -(positions.first().copied().unwrap_or(0) as i64);
+    let mut previous: Option<usize> = None;
+    for &position in positions {
+        total += MATCH_SCORE;
+        if position == 0 || !text[position - 1].is_alphanumeric() {
+            total += WORD_START_BONUS;
+        }
+        match previous {
+            Some(previous) if position == previous + 1 => total += CONSECUTIVE_BONUS,
+            Some(previous) => total -= (position - previous - 1) as i64,
+            None => {}
+        }
+        previous = Some(position);
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked_names(packages: &[PackageInfo], query: &str) -> Vec<String> {
+        rank(packages, query)
+            .into_iter()
+            .map(|pkg| pkg.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_ranking_tiers() {
+        let packages = vec![
+            PackageInfo::fixture("httpie").with_description("User-friendly cURL replacement"),
+            PackageInfo::fixture("git-gui").with_description("Tcl/Tk UI for git"),
+            PackageInfo::fixture("lazygit").with_description("Simple terminal UI for git commands"),
+            PackageInfo::fixture("git").with_description("Distributed revision control system"),
+            PackageInfo::fixture("jq").with_description("Lightweight JSON processor"),
+            PackageInfo::fixture("gh").with_description("GitHub command-line tool"),
+        ];
+
+        assert_eq!(
+            ranked_names(&packages, "git"),
+            ["git", "git-gui", "lazygit", "gh"]
+        );
+        assert_eq!(ranked_names(&packages, "GIT")[0], "git");
+        assert_eq!(ranked_names(&packages, "htp"), ["httpie"]);
+        assert_eq!(ranked_names(&packages, "json"), ["jq"]);
+        assert_eq!(ranked_names(&packages, "zzz"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_empty_query_keeps_order() {
+        let packages = vec![PackageInfo::fixture("wget"), PackageInfo::fixture("curl")];
+        assert_eq!(ranked_names(&packages, "  "), ["wget", "curl"]);
+    }
+
+    #[test]
+    fn test_match_positions() {
+        let found = match_package(
+            &PackageInfo::fixture("node-build").with_description("Install NodeJS versions"),
+            "nb",
+        )
+        .unwrap();
+        assert_eq!(found.tier, MatchTier::NameFuzzy);
+        assert_eq!(found.name_positions, [0, 5]);
+        assert!(found.description_positions.is_empty());
+
+        let found = match_package(
+            &PackageInfo::fixture("node").with_description("Install NodeJS versions"),
+            "node",
+        )
+        .unwrap();
+        assert_eq!(found.tier, MatchTier::ExactName);
+        assert_eq!(found.description_positions, [8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn test_matches_notes_last() {
+        let mut libpq = PackageInfo::fixture("libpq").with_description("Postgres C API library");
+        libpq.note = Some("Needed to build psycopg".to_string());
+        let packages = vec![libpq, PackageInfo::fixture("psycopg-build")];
+
+        assert_eq!(
+            ranked_names(&packages, "psycopg"),
+            ["psycopg-build", "libpq"]
+        );
+        let found = match_package(&packages[0], "BUILD").unwrap();
+        assert_eq!(found.tier, MatchTier::Note);
+        assert_eq!(found.note_positions, [10, 11, 12, 13, 14]);
+    }
+
+    #[test]
+    fn test_fuzzy_prefers_runs_and_word_starts() {
+        let text = fold("python-yq");
+        assert_eq!(fuzzy_positions(&text, &fold("yq")), Some(vec![7, 8]));
+        assert_eq!(fuzzy_positions(&text, &fold("qy")), None);
+    }
+}
@@ -1,7 +1,18 @@
 use crate::entities::brew_info_response::BrewInfoResponse;
 use anyhow::Result;
 use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Gets the current time as a Unix timestamp
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
 
 /// Formats a duration in seconds into a human-readable "time ago" string
 pub fn format_time_ago(seconds: u64) -> String {
@@ -59,6 +70,37 @@ pub fn format_time_ago(seconds: u64) -> String {
     }
 }
 
+/// Formats a Unix timestamp as a UTC date and time, e.g. "2024-09-22 14:03"
+pub fn format_date(timestamp: u64) -> String {
+    const DAY: u64 = 24 * 60 * 60;
+
+    // Convert days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let days = (timestamp / DAY) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    let seconds_of_day = timestamp % DAY;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60
+    )
+}
+
 /// Compare two Homebrew version strings, considering revision suffixes (_X)
 /// Returns Ordering::Less if a < b, Ordering::Equal if a == b, Ordering::Greater if a > b
 pub fn compare_homebrew_versions(a: &str, b: &str) -> Ordering {
@@ -120,6 +162,60 @@ pub fn brew_update() -> Result<()> {
     Ok(())
 }
 
+/// Gets the path of the Homebrew Cellar, where formula kegs are installed
+pub fn brew_cellar() -> Result<PathBuf> {
+    let output = Command::new("brew").arg("--cellar").output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("brew --cellar command failed"));
+    }
+
+    Ok(PathBuf::from(String::from_utf8(output.stdout)?.trim()))
+}
+
+/// Gets the path of the Homebrew Caskroom, where casks are installed
+pub fn brew_caskroom() -> Result<PathBuf> {
+    let output = Command::new("brew").arg("--caskroom").output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("brew --caskroom command failed"));
+    }
+
+    Ok(PathBuf::from(String::from_utf8(output.stdout)?.trim()))
+}
+
+/// Formats a size in bytes using binary units, e.g. "12.3 MB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Adds up the size of every file under a directory, without following symlinks
+pub fn directory_size(path: &Path) -> Option<u64> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return Some(metadata.len());
+    }
+
+    let mut total = 0;
+    for entry in fs::read_dir(path).ok()?.flatten() {
+        total += directory_size(&entry.path()).unwrap_or(0);
+    }
+    Some(total)
+}
+
 pub fn brew_info_all_installed() -> Result<BrewInfoResponse> {
     let output = Command::new("brew")
         .args(["info", "--json=v2", "--installed"])
@@ -136,17 +232,43 @@ pub fn brew_info_all_installed() -> Result<BrewInfoResponse> {
     Ok(response)
 }
 
+/// Expands a leading `~` in a path typed by the user to their home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Opens a URL in the default browser
+pub fn open_url(url: &str) -> Result<()> {
+    webbrowser::open(url).map_err(|e| anyhow::anyhow!("Failed to open browser: {}", e))?;
+    Ok(())
+}
+
 /// Opens the GitHub issues page in the default browser
 pub fn open_github_issues() -> Result<()> {
-    webbrowser::open("https://github.com/cosmincatalin/brew-explorer/issues")
-        .map_err(|e| anyhow::anyhow!("Failed to open browser: {}", e))?;
-    Ok(())
+    open_url("https://github.com/cosmincatalin/brew-explorer/issues")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_home("~/Brewfile"), home.join("Brewfile"));
+        assert_eq!(expand_home("~"), home);
+        assert_eq!(
+            expand_home("~other/Brewfile"),
+            PathBuf::from("~other/Brewfile")
+        );
+        assert_eq!(expand_home("/tmp/Brewfile"), PathBuf::from("/tmp/Brewfile"));
+    }
+
     #[test]
     fn test_compare_homebrew_versions_with_revisions() {
         // Test revision comparison
@@ -191,6 +313,13 @@ mod tests {
         assert_eq!(split_version_revision("1.0.0"), ("1.0.0".to_string(), 0));
     }
 
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01 00:00");
+        assert_eq!(format_date(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_date(1_727_000_000), "2024-09-22 10:13");
+    }
+
     #[test]
     fn test_compare_version_strings() {
         assert_eq!(compare_version_strings("3.2.4", "3.2.4"), Ordering::Equal);
@@ -202,4 +331,24 @@ mod tests {
         );
         assert_eq!(compare_version_strings("3.2.4", "3.10.1"), Ordering::Less);
     }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(12_897_485), "12.3 MB");
+    }
+
+    #[test]
+    fn test_directory_size() {
+        let dir = std::env::temp_dir().join(format!("brew-explorer-size-{}", std::process::id()));
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        fs::write(dir.join("bin").join("tool"), vec![0u8; 100]).unwrap();
+        fs::write(dir.join("README"), vec![0u8; 20]).unwrap();
+
+        let size = directory_size(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(size, Some(120));
+    }
 }
@@ -0,0 +1,921 @@
+use crate::config::KeysConfig;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Everything a key can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    OpenIssues,
+    MoveDown,
+    MoveUp,
+    MoveLeft,
+    MoveRight,
+    PageDown,
+    PageUp,
+    First,
+    Last,
+    Search,
+    EndSearch,
+    DeleteChar,
+    DeleteNextChar,
+    DeleteWord,
+    DeleteToStart,
+    CursorLeft,
+    CursorRight,
+    CursorStart,
+    CursorEnd,
+    SubmitSearch,
+    HistoryPrevious,
+    HistoryNext,
+    SaveSearch,
+    SavedSearches,
+    RemoveEntry,
+    Complete,
+    Update,
+    Uninstall,
+    Undo,
+    EditNote,
+    TogglePin,
+    OpenHomepage,
+    Export,
+    CommandPalette,
+    CommandLine,
+    EditCollections,
+    UpdateCollection,
+    UninstallCollection,
+    Refresh,
+    Timeline,
+    History,
+    FilterPackage,
+    OpenEntry,
+    Confirm,
+    Cancel,
+    Help,
+    CycleSort,
+    FilterFormulae,
+    FilterCasks,
+    FilterOutdated,
+    FilterPinned,
+    FilterTap,
+    FilterCollection,
+    ClearFilters,
+}
+
+impl Action {
+    pub const ALL: [Action; 55] = [
+        Action::Quit,
+        Action::OpenIssues,
+        Action::MoveDown,
+        Action::MoveUp,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::PageDown,
+        Action::PageUp,
+        Action::First,
+        Action::Last,
+        Action::Search,
+        Action::EndSearch,
+        Action::DeleteChar,
+        Action::DeleteNextChar,
+        Action::DeleteWord,
+        Action::DeleteToStart,
+        Action::CursorLeft,
+        Action::CursorRight,
+        Action::CursorStart,
+        Action::CursorEnd,
+        Action::SubmitSearch,
+        Action::HistoryPrevious,
+        Action::HistoryNext,
+        Action::SaveSearch,
+        Action::SavedSearches,
+        Action::RemoveEntry,
+        Action::Complete,
+        Action::Update,
+        Action::Uninstall,
+        Action::Undo,
+        Action::EditNote,
+        Action::TogglePin,
+        Action::OpenHomepage,
+        Action::Export,
+        Action::CommandPalette,
+        Action::CommandLine,
+        Action::EditCollections,
+        Action::UpdateCollection,
+        Action::UninstallCollection,
+        Action::Refresh,
+        Action::Timeline,
+        Action::History,
+        Action::FilterPackage,
+        Action::OpenEntry,
+        Action::Confirm,
+        Action::Cancel,
+        Action::Help,
+        Action::CycleSort,
+        Action::FilterFormulae,
+        Action::FilterCasks,
+        Action::FilterOutdated,
+        Action::FilterPinned,
+        Action::FilterTap,
+        Action::FilterCollection,
+        Action::ClearFilters,
+    ];
+
+    /// Gets the name used for the action in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::OpenIssues => "open_issues",
+            Action::MoveDown => "move_down",
+            Action::MoveUp => "move_up",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Search => "search",
+            Action::EndSearch => "end_search",
+            Action::DeleteChar => "delete_char",
+            Action::DeleteNextChar => "delete_next_char",
+            Action::DeleteWord => "delete_word",
+            Action::DeleteToStart => "delete_to_start",
+            Action::CursorLeft => "cursor_left",
+            Action::CursorRight => "cursor_right",
+            Action::CursorStart => "cursor_start",
+            Action::CursorEnd => "cursor_end",
+            Action::SubmitSearch => "submit_search",
+            Action::HistoryPrevious => "history_previous",
+            Action::HistoryNext => "history_next",
+            Action::SaveSearch => "save_search",
+            Action::SavedSearches => "saved_searches",
+            Action::RemoveEntry => "remove_entry",
+            Action::Complete => "complete",
+            Action::Update => "update",
+            Action::Uninstall => "uninstall",
+            Action::Undo => "undo",
+            Action::EditNote => "edit_note",
+            Action::TogglePin => "toggle_pin",
+            Action::OpenHomepage => "open_homepage",
+            Action::Export => "export",
+            Action::CommandPalette => "command_palette",
+            Action::CommandLine => "command_line",
+            Action::EditCollections => "edit_collections",
+            Action::UpdateCollection => "update_collection",
+            Action::UninstallCollection => "uninstall_collection",
+            Action::Refresh => "refresh",
+            Action::Timeline => "timeline",
+            Action::History => "history",
+            Action::FilterPackage => "filter_package",
+            Action::OpenEntry => "open_entry",
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::Help => "help",
+            Action::CycleSort => "sort",
+            Action::FilterFormulae => "filter_formulae",
+            Action::FilterCasks => "filter_casks",
+            Action::FilterOutdated => "filter_outdated",
+            Action::FilterPinned => "filter_pinned",
+            Action::FilterTap => "filter_tap",
+            Action::FilterCollection => "filter_collection",
+            Action::ClearFilters => "clear_filters",
+        }
+    }
+
+    /// Gets a short description for the help overlay
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::OpenIssues => "Submit an issue on GitHub",
+            Action::MoveDown => "Move down",
+            Action::MoveUp => "Move up",
+            Action::MoveLeft => "Move to the column on the left",
+            Action::MoveRight => "Move to the column on the right",
+            Action::PageDown => "Jump a page down",
+            Action::PageUp => "Jump a page up",
+            Action::First => "Jump to the first item",
+            Action::Last => "Jump to the last item",
+            Action::Search => "Search packages",
+            Action::EndSearch => "Finish searching",
+            Action::DeleteChar => "Delete the character before the cursor",
+            Action::DeleteNextChar => "Delete the character after the cursor",
+            Action::DeleteWord => "Delete the word before the cursor",
+            Action::DeleteToStart => "Delete everything before the cursor",
+            Action::CursorLeft => "Move the cursor left",
+            Action::CursorRight => "Move the cursor right",
+            Action::CursorStart => "Move the cursor to the start",
+            Action::CursorEnd => "Move the cursor to the end",
+            Action::SubmitSearch => "Finish searching and remember the query",
+            Action::HistoryPrevious => "Recall an earlier query",
+            Action::HistoryNext => "Recall a later query",
+            Action::SaveSearch => "Save the search and filters under a name",
+            Action::SavedSearches => "Bring back a saved search",
+            Action::RemoveEntry => "Remove the selected entry",
+            Action::Complete => "Complete the word before the cursor",
+            Action::Update => "Update the selected package",
+            Action::Uninstall => "Uninstall the selected package",
+            Action::Undo => "Undo the latest uninstall, or the selected one in the history",
+            Action::EditNote => "Add or edit a note on the selected package",
+            Action::TogglePin => "Pin or unpin the selected formula",
+            Action::OpenHomepage => "Open the homepage of the selected package",
+            Action::Export => "Export the shown packages to a file",
+            Action::CommandPalette => "Search every action",
+            Action::CommandLine => "Type a command, like upgrade jq or sort date",
+            Action::EditCollections => "Add the selected package to a collection, or remove it",
+            Action::UpdateCollection => "Update every outdated package of the browsed collection",
+            Action::UninstallCollection => "Uninstall every package of the browsed collection",
+            Action::Refresh => "Reload all packages from Homebrew in the background",
+            Action::Timeline => "Show or hide the install timeline",
+            Action::History => "Show or hide the history of upgrades, uninstalls and installs",
+            Action::FilterPackage => {
+                "Only show the selected entry's package, or every package again"
+            }
+            Action::OpenEntry => "Show the package in the package list",
+            Action::Confirm => "Confirm",
+            Action::Cancel => "Cancel",
+            Action::Help => "Show the key bindings",
+            Action::CycleSort => "Cycle the sort order",
+            Action::FilterFormulae => "Show only formulae",
+            Action::FilterCasks => "Show only casks",
+            Action::FilterOutdated => "Show only outdated packages",
+            Action::FilterPinned => "Show only pinned formulae",
+            Action::FilterTap => "Show only the next tap",
+            Action::FilterCollection => "Show only the next collection",
+            Action::ClearFilters => "Clear all filters",
+        }
+    }
+
+    /// Looks up an action by its config file name
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+}
+
+/// The part of the UI that receives key presses, each with its own bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyMode {
+    Normal,
+    Search,
+    Timeline,
+    History,
+    Confirm,
+    Progress,
+    Prompt,
+    Picker,
+    Command,
+}
+
+impl KeyMode {
+    const ALL: [KeyMode; 9] = [
+        KeyMode::Normal,
+        KeyMode::Search,
+        KeyMode::Timeline,
+        KeyMode::History,
+        KeyMode::Confirm,
+        KeyMode::Progress,
+        KeyMode::Prompt,
+        KeyMode::Picker,
+        KeyMode::Command,
+    ];
+
+    /// Returns true if unbound printable keys are typed into a text input in this mode
+    pub fn takes_text(&self) -> bool {
+        matches!(
+            self,
+            KeyMode::Search | KeyMode::Prompt | KeyMode::Picker | KeyMode::Command
+        )
+    }
+
+    /// Gets the name of the mode's `[keys.*]` table in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyMode::Normal => "normal",
+            KeyMode::Search => "search",
+            KeyMode::Timeline => "timeline",
+            KeyMode::History => "history",
+            KeyMode::Confirm => "confirm",
+            KeyMode::Progress => "progress",
+            KeyMode::Prompt => "prompt",
+            KeyMode::Picker => "picker",
+            KeyMode::Command => "command",
+        }
+    }
+
+    /// Gets a human-readable title for the help overlay
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyMode::Normal => "Package List",
+            KeyMode::Search => "Search",
+            KeyMode::Timeline => "Install Timeline",
+            KeyMode::History => "Operation History",
+            KeyMode::Confirm => "Uninstall Confirmation",
+            KeyMode::Progress => "Update Progress",
+            KeyMode::Prompt => "Prompt",
+            KeyMode::Picker => "Picker",
+            KeyMode::Command => "Command Line",
+        }
+    }
+
+    /// Returns true if the action can be bound in this mode
+    pub fn supports(&self, action: Action) -> bool {
+        self.default_bindings()
+            .iter()
+            .any(|(supported, _)| *supported == action)
+    }
+
+    /// Gets the built-in bindings of the mode, which also define the actions it supports
+    fn default_bindings(&self) -> &'static [(Action, &'static [&'static str])] {
+        match self {
+            KeyMode::Normal => &[
+                (Action::MoveDown, &["down", "j"]),
+                (Action::MoveUp, &["up", "k"]),
+                (Action::MoveLeft, &["left", "h"]),
+                (Action::MoveRight, &["right", "l"]),
+                (Action::PageDown, &["pagedown"]),
+                (Action::PageUp, &["pageup"]),
+                (Action::First, &["home"]),
+                (Action::Last, &["end"]),
+                (Action::Search, &["/"]),
+                (Action::Update, &["u"]),
+                (Action::Uninstall, &["x"]),
+                (Action::Undo, &["z"]),
+                (Action::EditNote, &["n"]),
+                (Action::TogglePin, &["P"]),
+                (Action::OpenHomepage, &["w"]),
+                (Action::Export, &["E"]),
+                (Action::EditCollections, &["a"]),
+                (Action::UpdateCollection, &["U"]),
+                (Action::UninstallCollection, &["X"]),
+                (Action::Refresh, &["r"]),
+                (Action::CycleSort, &["s"]),
+                (Action::FilterFormulae, &["f"]),
+                (Action::FilterCasks, &["c"]),
+                (Action::FilterOutdated, &["o"]),
+                (Action::FilterPinned, &["p"]),
+                (Action::FilterTap, &["T"]),
+                (Action::FilterCollection, &["C"]),
+                (Action::ClearFilters, &["0"]),
+                (Action::SavedSearches, &["S"]),
+                (Action::CommandPalette, &["ctrl-p"]),
+                (Action::CommandLine, &[":"]),
+                (Action::Timeline, &["t"]),
+                (Action::History, &["H"]),
+                (Action::OpenIssues, &["g"]),
+                (Action::Help, &["?"]),
+                (Action::Quit, &["q"]),
+            ],
+            KeyMode::Search => &[
+                (Action::SubmitSearch, &["enter"]),
+                (Action::EndSearch, &["esc"]),
+                (Action::MoveDown, &["ctrl-n"]),
+                (Action::MoveUp, &["ctrl-p"]),
+                (Action::PageDown, &["pagedown"]),
+                (Action::PageUp, &["pageup"]),
+                (Action::CursorLeft, &["left", "ctrl-b"]),
+                (Action::CursorRight, &["right", "ctrl-f"]),
+                (Action::CursorStart, &["home", "ctrl-a"]),
+                (Action::CursorEnd, &["end", "ctrl-e"]),
+                (Action::DeleteChar, &["backspace", "ctrl-h"]),
+                (Action::DeleteNextChar, &["delete", "ctrl-d"]),
+                (Action::DeleteWord, &["ctrl-w"]),
+                (Action::DeleteToStart, &["ctrl-u"]),
+                (Action::HistoryPrevious, &["up"]),
+                (Action::HistoryNext, &["down"]),
+                (Action::SaveSearch, &["ctrl-s"]),
+                (Action::CommandPalette, &["ctrl-k"]),
+            ],
+            KeyMode::Timeline => &[
+                (Action::MoveDown, &["down", "j"]),
+                (Action::MoveUp, &["up", "k"]),
+                (Action::PageDown, &["pagedown"]),
+                (Action::PageUp, &["pageup"]),
+                (Action::First, &["home"]),
+                (Action::Last, &["end"]),
+                (Action::OpenEntry, &["enter"]),
+                (Action::Timeline, &["t", "esc"]),
+                (Action::CommandPalette, &["ctrl-p"]),
+                (Action::OpenIssues, &["g"]),
+                (Action::Help, &["?"]),
+                (Action::Quit, &["q"]),
+            ],
+            KeyMode::History => &[
+                (Action::MoveDown, &["down", "j"]),
+                (Action::MoveUp, &["up", "k"]),
+                (Action::PageDown, &["pagedown"]),
+                (Action::PageUp, &["pageup"]),
+                (Action::First, &["home"]),
+                (Action::Last, &["end"]),
+                (Action::FilterPackage, &["f"]),
+                (Action::Undo, &["z"]),
+                (Action::OpenEntry, &["enter"]),
+                (Action::History, &["H", "esc"]),
+                (Action::CommandPalette, &["ctrl-p"]),
+                (Action::OpenIssues, &["g"]),
+                (Action::Help, &["?"]),
+                (Action::Quit, &["q"]),
+            ],
+            KeyMode::Confirm => &[
+                (Action::Confirm, &["y", "Y", "enter"]),
+                (Action::Cancel, &["n", "N", "esc"]),
+                (Action::OpenIssues, &["g"]),
+                (Action::Quit, &["q"]),
+            ],
+            KeyMode::Progress => &[(Action::OpenIssues, &["g"]), (Action::Quit, &["q"])],
+            KeyMode::Prompt => &[
+                (Action::Confirm, &["enter"]),
+                (Action::Cancel, &["esc"]),
+                (Action::CursorLeft, &["left", "ctrl-b"]),
+                (Action::CursorRight, &["right", "ctrl-f"]),
+                (Action::CursorStart, &["home", "ctrl-a"]),
+                (Action::CursorEnd, &["end", "ctrl-e"]),
+                (Action::DeleteChar, &["backspace", "ctrl-h"]),
+                (Action::DeleteNextChar, &["delete", "ctrl-d"]),
+                (Action::DeleteWord, &["ctrl-w"]),
+                (Action::DeleteToStart, &["ctrl-u"]),
+            ],
+            KeyMode::Picker => &[
+                (Action::Confirm, &["enter"]),
+                (Action::Cancel, &["esc"]),
+                (Action::MoveDown, &["down", "ctrl-n"]),
+                (Action::MoveUp, &["up", "ctrl-p"]),
+                (Action::DeleteChar, &["backspace", "ctrl-h"]),
+                (Action::DeleteWord, &["ctrl-w"]),
+                (Action::DeleteToStart, &["ctrl-u"]),
+                (Action::RemoveEntry, &["ctrl-d"]),
+            ],
+            KeyMode::Command => &[
+                (Action::Confirm, &["enter"]),
+                (Action::Cancel, &["esc"]),
+                (Action::Complete, &["tab"]),
+                (Action::HistoryPrevious, &["up", "ctrl-p"]),
+                (Action::HistoryNext, &["down", "ctrl-n"]),
+                (Action::CursorLeft, &["left", "ctrl-b"]),
+                (Action::CursorRight, &["right", "ctrl-f"]),
+                (Action::CursorStart, &["home", "ctrl-a"]),
+                (Action::CursorEnd, &["end", "ctrl-e"]),
+                (Action::DeleteChar, &["backspace", "ctrl-h"]),
+                (Action::DeleteNextChar, &["delete", "ctrl-d"]),
+                (Action::DeleteWord, &["ctrl-w"]),
+                (Action::DeleteToStart, &["ctrl-u"]),
+            ],
+        }
+    }
+}
+
+/// A single key press, with Shift folded into the character for printable keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyPress {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    /// Creates a key press from a terminal key event
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::normalised(
+            key.code,
+            key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT),
+        )
+    }
+
+    /// Folds Shift into characters and Shift-Tab, so "G" matches however the terminal reports it
+    fn normalised(code: KeyCode, mut modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::Tab | KeyCode::BackTab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Parses a key written as in the config file, such as "j", "G", "pagedown" or "ctrl-d"
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        while let Some((prefix, tail)) = rest.split_once('-') {
+            // A trailing "-" is the minus key itself
+            if tail.is_empty() {
+                break;
+            }
+            let modifier = match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            modifiers.insert(modifier);
+            rest = tail;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "tab" => KeyCode::Tab,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key \"{}\"", text)),
+                },
+            },
+        };
+        Ok(Self::normalised(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::BackTab => write!(f, "Shift-Tab"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// One or more key presses that trigger an action, such as "d d"
+pub type KeySequence = Vec<KeyPress>;
+
+/// Parses a space-separated key sequence
+fn parse_sequence(text: &str) -> Result<KeySequence, String> {
+    let sequence = text
+        .split_whitespace()
+        .map(KeyPress::parse)
+        .collect::<Result<KeySequence, String>>()?;
+    if sequence.is_empty() {
+        return Err("empty key".to_string());
+    }
+    Ok(sequence)
+}
+
+/// Formats a key sequence for display
+pub fn format_sequence(sequence: &[KeyPress]) -> String {
+    sequence
+        .iter()
+        .map(KeyPress::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The key sequences bound to an action
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub action: Action,
+    pub sequences: Vec<KeySequence>,
+}
+
+/// What the keys pressed so far amount to
+#[derive(Debug, PartialEq)]
+pub enum KeyResolution {
+    Action(Action),
+    Pending, // The start of a chord, waiting for more keys
+    Unbound,
+}
+
+/// The active key bindings of every mode
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    modes: HashMap<KeyMode, Vec<Binding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&KeysConfig::default()).expect("the default key bindings are valid")
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap from the defaults and the `[keys.*]` overrides in the config.
+    /// An override replaces every default key of that action in that mode.
+    pub fn new(config: &KeysConfig) -> Result<Self> {
+        let mut modes = HashMap::new();
+        for mode in KeyMode::ALL {
+            let mut bindings: Vec<Binding> = mode
+                .default_bindings()
+                .iter()
+                .map(|(action, keys)| Binding {
+                    action: *action,
+                    sequences: keys
+                        .iter()
+                        .map(|key| parse_sequence(key).expect("default keys parse"))
+                        .collect(),
+                })
+                .collect();
+
+            for (name, keys) in config.mode(mode) {
+                let binding = Action::from_name(name)
+                    .and_then(|action| bindings.iter_mut().find(|b| b.action == action))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "keys.{}: unknown action `{}` in this mode",
+                            mode.name(),
+                            name
+                        )
+                    })?;
+                binding.sequences = keys
+                    .iter()
+                    .map(|key| parse_sequence(key))
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| anyhow::anyhow!("keys.{}.{}: {}", mode.name(), name, e))?;
+            }
+
+            check_conflicts(mode, &bindings)?;
+            modes.insert(mode, bindings);
+        }
+        Ok(Self { modes })
+    }
+
+    /// Gets the bindings of a mode, in help overlay order
+    pub fn bindings(&self, mode: KeyMode) -> &[Binding] {
+        self.modes.get(&mode).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Adds a key press to the pending chord and looks up what it triggers.
+    /// A key that breaks a chord is looked up again on its own.
+    pub fn resolve(
+        &self,
+        mode: KeyMode,
+        pending: &mut KeySequence,
+        key: KeyPress,
+    ) -> KeyResolution {
+        pending.push(key);
+
+        let mut is_prefix = false;
+        for binding in self.bindings(mode) {
+            for sequence in &binding.sequences {
+                if sequence == pending {
+                    pending.clear();
+                    return KeyResolution::Action(binding.action);
+                }
+                if sequence.starts_with(pending) {
+                    is_prefix = true;
+                }
+            }
+        }
+        if is_prefix {
+            return KeyResolution::Pending;
+        }
+
+        let was_chord = pending.len() > 1;
+        pending.clear();
+        if was_chord {
+            self.resolve(mode, pending, key)
+        } else {
+            KeyResolution::Unbound
+        }
+    }
+
+    /// Gets the keys of an action for hints, such as "x" or "↑/k"
+    pub fn keys_label(&self, mode: KeyMode, action: Action) -> String {
+        let label = self
+            .bindings(mode)
+            .iter()
+            .filter(|binding| binding.action == action)
+            .flat_map(|binding| binding.sequences.iter().map(|s| format_sequence(s)))
+            .collect::<Vec<_>>()
+            .join("/");
+        if label.is_empty() {
+            "unbound".to_string()
+        } else {
+            label
+        }
+    }
+
+    /// Gets the first key of an action, for compact hints
+    pub fn first_key(&self, mode: KeyMode, action: Action) -> String {
+        self.bindings(mode)
+            .iter()
+            .find(|binding| binding.action == action)
+            .and_then(|binding| binding.sequences.first())
+            .map(|sequence| format_sequence(sequence))
+            .unwrap_or_else(|| "unbound".to_string())
+    }
+}
+
+/// Rejects key sequences bound twice, or that start another sequence and so could never finish it
+fn check_conflicts(mode: KeyMode, bindings: &[Binding]) -> Result<()> {
+    let mut seen: Vec<(&KeySequence, Action)> = Vec::new();
+    for binding in bindings {
+        for sequence in &binding.sequences {
+            for (other, other_action) in &seen {
+                if other.starts_with(sequence) || sequence.starts_with(other) {
+                    let (shorter, shorter_action, longer, longer_action) =
+                        if sequence.len() <= other.len() {
+                            (sequence, binding.action, *other, *other_action)
+                        } else {
+                            (*other, *other_action, sequence, binding.action)
+                        };
+                    return Err(if shorter.len() == longer.len() {
+                        anyhow::anyhow!(
+                            "keys.{}: `{}` is bound to both {} and {}",
+                            mode.name(),
+                            format_sequence(shorter),
+                            longer_action.name(),
+                            shorter_action.name()
+                        )
+                    } else {
+                        anyhow::anyhow!(
+                            "keys.{}: `{}` ({}) is the start of `{}` ({})",
+                            mode.name(),
+                            format_sequence(shorter),
+                            shorter_action.name(),
+                            format_sequence(longer),
+                            longer_action.name()
+                        )
+                    });
+                }
+            }
+            seen.push((sequence, binding.action));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(text: &str) -> KeyPress {
+        KeyPress::parse(text).unwrap()
+    }
+
+    fn keys_config(mode: &str, action: &str, keys: &[&str]) -> KeysConfig {
+        let toml = format!(
+            "[{}]\n{} = [{}]\n",
+            mode,
+            action,
+            keys.iter()
+                .map(|key| format!("\"{}\"", key))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        toml::from_str(&toml).unwrap()
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(press("j").code, KeyCode::Char('j'));
+        assert_eq!(press("-").code, KeyCode::Char('-'));
+        assert_eq!(press("pagedown").code, KeyCode::PageDown);
+        assert_eq!(press("F5").code, KeyCode::F(5));
+        assert_eq!(press("shift-g"), press("G"));
+        assert_eq!(press("shift-tab").code, KeyCode::BackTab);
+
+        let ctrl_d = press("ctrl-D");
+        assert_eq!(ctrl_d.code, KeyCode::Char('d'));
+        assert_eq!(ctrl_d.modifiers, KeyModifiers::CONTROL);
+        assert_eq!(ctrl_d.to_string(), "Ctrl-d");
+
+        assert!(KeyPress::parse("hyper-x").is_err());
+        assert!(KeyPress::parse("f13").is_err());
+    }
+
+    #[test]
+    fn test_key_events_are_normalised() {
+        let shifted = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(KeyPress::from_event(&shifted), press("G"));
+    }
+
+    #[test]
+    fn test_default_bindings_resolve() {
+        let keymap = Keymap::default();
+        let mut pending = Vec::new();
+
+        assert_eq!(
+            keymap.resolve(KeyMode::Normal, &mut pending, press("x")),
+            KeyResolution::Action(Action::Uninstall)
+        );
+        assert_eq!(
+            keymap.resolve(KeyMode::Confirm, &mut pending, press("Y")),
+            KeyResolution::Action(Action::Confirm)
+        );
+        assert_eq!(
+            keymap.resolve(KeyMode::Search, &mut pending, press("z")),
+            KeyResolution::Unbound
+        );
+        // Letters are typed into the search prompt, never bound
+        for letter in ["j", "k", "h", "l", "g"] {
+            assert_eq!(
+                keymap.resolve(KeyMode::Search, &mut pending, press(letter)),
+                KeyResolution::Unbound
+            );
+        }
+        assert_eq!(
+            keymap.resolve(KeyMode::Search, &mut pending, press("ctrl-n")),
+            KeyResolution::Action(Action::MoveDown)
+        );
+        assert_eq!(keymap.keys_label(KeyMode::Normal, Action::MoveUp), "↑/k");
+
+        // The command palette opens from the other screens too
+        assert_eq!(
+            keymap.resolve(KeyMode::Search, &mut pending, press("ctrl-k")),
+            KeyResolution::Action(Action::CommandPalette)
+        );
+        assert!(KeyMode::History.supports(Action::CommandPalette));
+        assert!(!KeyMode::Normal.supports(Action::SubmitSearch));
+    }
+
+    #[test]
+    fn test_chords() {
+        let keymap = Keymap::new(&keys_config("normal", "uninstall", &["d d"])).unwrap();
+        let mut pending = Vec::new();
+
+        assert_eq!(
+            keymap.resolve(KeyMode::Normal, &mut pending, press("d")),
+            KeyResolution::Pending
+        );
+        assert_eq!(
+            keymap.resolve(KeyMode::Normal, &mut pending, press("d")),
+            KeyResolution::Action(Action::Uninstall)
+        );
+        assert!(pending.is_empty());
+
+        // A key that breaks the chord counts on its own
+        keymap.resolve(KeyMode::Normal, &mut pending, press("d"));
+        assert_eq!(
+            keymap.resolve(KeyMode::Normal, &mut pending, press("j")),
+            KeyResolution::Action(Action::MoveDown)
+        );
+
+        // The override replaces the default key
+        assert_eq!(
+            keymap.resolve(KeyMode::Normal, &mut pending, press("x")),
+            KeyResolution::Unbound
+        );
+    }
+
+    #[test]
+    fn test_invalid_overrides_are_reported() {
+        let unknown = Keymap::new(&keys_config("normal", "explode", &["e"])).unwrap_err();
+        assert!(
+            unknown
+                .to_string()
+                .contains("keys.normal: unknown action `explode`")
+        );
+
+        let wrong_mode = Keymap::new(&keys_config("search", "uninstall", &["x"])).unwrap_err();
+        assert!(wrong_mode.to_string().contains("keys.search"));
+
+        let bad_key = Keymap::new(&keys_config("normal", "quit", &["ctrl-"])).unwrap_err();
+        assert!(bad_key.to_string().contains("keys.normal.quit"));
+
+        let duplicate = Keymap::new(&keys_config("normal", "uninstall", &["u"])).unwrap_err();
+        assert!(
+            duplicate
+                .to_string()
+                .contains("`u` is bound to both update and uninstall")
+        );
+
+        let prefix = Keymap::new(&keys_config("normal", "uninstall", &["g g"])).unwrap_err();
+        assert!(
+            prefix
+                .to_string()
+                .contains("`g` (open_issues) is the start of `g g` (uninstall)")
+        );
+    }
+}
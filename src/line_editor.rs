@@ -0,0 +1,193 @@
+/// A single line of editable text with a cursor, for prompts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineEditor {
+    chars: Vec<char>,
+    cursor: usize, // Index of the character the cursor is before
+}
+
+impl LineEditor {
+    /// Gets the text
+    pub fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    /// Gets the text before the cursor
+    pub fn text_before_cursor(&self) -> String {
+        self.chars[..self.cursor].iter().collect()
+    }
+
+    /// Replaces the text, with the cursor at the end
+    pub fn set_text(&mut self, text: &str) {
+        self.chars = text.chars().collect();
+        self.cursor = self.chars.len();
+    }
+
+    /// Removes all text
+    pub fn clear(&mut self) {
+        self.set_text("");
+    }
+
+    /// Inserts a character at the cursor
+    pub fn insert(&mut self, c: char) {
+        self.chars.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    /// Inserts pasted text at the cursor, turning line breaks and tabs into spaces
+    pub fn insert_str(&mut self, text: &str) {
+        let text = text.trim_end_matches(['\r', '\n']);
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.insert(' ');
+            } else if !c.is_control() {
+                self.insert(c);
+            }
+        }
+    }
+
+    /// Deletes the character before the cursor
+    pub fn delete_before(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    /// Deletes the character after the cursor
+    pub fn delete_after(&mut self) {
+        if self.cursor < self.chars.len() {
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    /// Deletes the word before the cursor, along with any spaces after it, like Ctrl-W in a shell
+    pub fn delete_word_before(&mut self) {
+        let mut start = self.cursor;
+        while start > 0 && self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        while start > 0 && !self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        self.chars.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Replaces the word before the cursor, back to the previous space, with the given text
+    pub fn replace_word_before(&mut self, text: &str) {
+        let mut start = self.cursor;
+        while start > 0 && !self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        self.chars.drain(start..self.cursor);
+        self.cursor = start;
+        for c in text.chars() {
+            self.insert(c);
+        }
+    }
+
+    /// Deletes everything before the cursor, like Ctrl-U in a shell
+    pub fn delete_to_start(&mut self) {
+        self.chars.drain(..self.cursor);
+        self.cursor = 0;
+    }
+
+    /// Moves the cursor one character to the left
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Moves the cursor one character to the right
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.chars.len());
+    }
+
+    /// Moves the cursor to the start of the line
+    pub fn move_to_start(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Moves the cursor to the end of the line
+    pub fn move_to_end(&mut self) {
+        self.cursor = self.chars.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor_with(text: &str) -> LineEditor {
+        let mut editor = LineEditor::default();
+        editor.set_text(text);
+        editor
+    }
+
+    #[test]
+    fn test_insert_and_delete_at_cursor() {
+        let mut editor = editor_with("hlm");
+        editor.move_left();
+        editor.move_left();
+        editor.insert('e');
+        assert_eq!(editor.text(), "helm");
+        assert_eq!(editor.text_before_cursor(), "he");
+
+        editor.delete_after();
+        editor.delete_before();
+        assert_eq!(editor.text(), "hm");
+
+        editor.move_to_start();
+        editor.delete_before();
+        editor.move_to_end();
+        editor.delete_after();
+        assert_eq!(editor.text(), "hm");
+    }
+
+    #[test]
+    fn test_unicode_text() {
+        let mut editor = editor_with("naïve");
+        editor.move_left();
+        editor.move_left();
+        editor.delete_before();
+        editor.insert('ï');
+        editor.insert('日');
+        assert_eq!(editor.text(), "naï日ve");
+    }
+
+    #[test]
+    fn test_delete_word_and_line() {
+        let mut editor = editor_with("type:cask  fire");
+        editor.delete_word_before();
+        assert_eq!(editor.text(), "type:cask  ");
+        editor.delete_word_before();
+        assert_eq!(editor.text(), "");
+
+        let mut editor = editor_with("outdated:true jq");
+        editor.move_left();
+        editor.move_left();
+        editor.delete_to_start();
+        assert_eq!(editor.text(), "jq");
+        assert_eq!(editor.text_before_cursor(), "");
+    }
+
+    #[test]
+    fn test_replace_word_before() {
+        let mut editor = editor_with("upgrade jq wg");
+        editor.replace_word_before("wget ");
+        assert_eq!(editor.text(), "upgrade jq wget ");
+
+        editor.replace_word_before("helm");
+        assert_eq!(editor.text(), "upgrade jq wget helm");
+
+        editor.move_to_start();
+        editor.replace_word_before("x");
+        assert_eq!(editor.text(), "xupgrade jq wget helm");
+    }
+
+    #[test]
+    fn test_paste_flattens_lines() {
+        let mut editor = editor_with("");
+        editor.insert_str("http\tclient\nwget\r\n");
+        assert_eq!(editor.text(), "http client wget");
+    }
+}
@@ -1,35 +1,136 @@
 mod app;
+mod check;
+mod cli;
+mod collections;
+mod command_line;
+mod commands;
+mod config;
+mod data_store;
 mod entities;
 mod events;
+mod export;
+mod filters;
+mod fuzzy;
 mod helpers;
+mod keymap;
+mod line_editor;
+mod notes;
+mod operation_log;
+mod package_cache;
+mod prompt;
+mod query;
 mod repository;
+mod search_store;
+mod snapshots;
+mod sort;
+mod theme;
 mod ui;
+mod ui_state;
 
 use anyhow::Result;
 use app::App;
+use clap::Parser;
+use cli::Cli;
+use config::Config;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind, poll},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyEventKind, poll,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use events::handle_key_event;
+use events::{handle_key_event, handle_mouse_event, handle_paste};
 use ratatui::{Terminal, backend::CrosstermBackend, prelude::Backend};
 use repository::HomebrewRepository;
 use std::{
-    io, thread,
+    io,
+    process::ExitCode,
+    thread,
     time::{Duration, Instant},
 };
+use theme::Theme;
 use ui::{render_loading_screen, render_ui};
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+
+    // Subcommands print their results without starting the TUI
+    if let Some(command) = &cli.command {
+        return commands::run(&cli, command);
+    }
+
+    // Load the config before touching the terminal so errors print normally
+    let config = Config::load()?;
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    let res = run_tui(&mut terminal, config, &cli);
+
+    // Restore terminal
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )?;
+    terminal.show_cursor()?;
+
+    // Errors are printed once the terminal is back to normal
+    res?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Loads the packages and runs the TUI until the user quits
+fn run_tui<B: Backend>(terminal: &mut Terminal<B>, config: Config, cli: &Cli) -> Result<()> {
+    // Render the last persisted package list right away if there is one, and refresh it
+    // in the background. Otherwise show the loading screen until the first load completes.
+    let app = match package_cache::load() {
+        Some(snapshot) => {
+            let mut app = App::new(HomebrewRepository::from_packages(snapshot.packages), config)?;
+            app.stale_since = Some(snapshot.saved_at);
+            app.refreshed_at = snapshot.saved_at;
+            app.start_background_reload(!cli.no_update);
+            Some(app)
+        }
+        None => load_with_loading_screen(terminal, config, !cli.no_update)?,
+    };
+
+    match app {
+        Some(mut app) => {
+            app.run_brew_update = !cli.no_update;
+            // Apply the initial filters and search query from the command line
+            app.set_filters(cli.filters());
+            if let Some(query) = &cli.search {
+                app.start_search_with_query(query);
+            }
+            run_app(terminal, &mut app)
+        }
+        None => Ok(()), // User quit while loading
+    }
+}
+
+/// Shows the loading screen while packages are loaded for the first time.
+/// Returns `None` if the user quit before loading finished.
+fn load_with_loading_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    config: Config,
+    run_brew_update: bool,
+) -> Result<Option<App>> {
     // Show loading screen while initializing
+    let theme = Theme::for_terminal(&config)?;
     let start_time = Instant::now();
     let mut loading_dots = 0;
     let mut last_dot_update = Instant::now();
@@ -38,16 +139,18 @@ fn main() -> Result<()> {
     let (tx, rx) = std::sync::mpsc::channel();
     thread::spawn(move || {
         // First, run brew update to fetch latest package information
-        let _ = helpers::brew_update();
+        if run_brew_update {
+            let _ = helpers::brew_update();
+        }
 
         // Then create repository and app
         let repository = HomebrewRepository::new();
-        let app = App::new(repository);
+        let app = App::new(repository, config);
         tx.send(app).unwrap();
     });
 
     // Show loading screen until app is ready (real loading time)
-    let mut app = loop {
+    loop {
         // Update loading animation every 200ms for smoother animation
         if last_dot_update.elapsed() >= Duration::from_millis(200) {
             loading_dots = (loading_dots + 1) % 4;
@@ -55,11 +158,13 @@ fn main() -> Result<()> {
         }
 
         // Render loading screen
-        terminal.draw(|f| render_loading_screen(f, loading_dots, start_time.elapsed()))?;
+        terminal.draw(|f| render_loading_screen(f, &theme, loading_dots, start_time.elapsed()))?;
 
         // Check if app is ready
         if let Ok(app_result) = rx.try_recv() {
-            break app_result?;
+            let app = app_result?;
+            let _ = package_cache::save(&app.items);
+            return Ok(Some(app));
         }
 
         // Handle any key events during loading (allow quit)
@@ -68,40 +173,15 @@ fn main() -> Result<()> {
             && key.kind == KeyEventKind::Press
             && key.code == event::KeyCode::Char('q')
         {
-            // Cleanup and exit
-            disable_raw_mode()?;
-            execute!(
-                terminal.backend_mut(),
-                LeaveAlternateScreen,
-                DisableMouseCapture
-            )?;
-            terminal.show_cursor()?;
-            return Ok(());
+            return Ok(None);
         }
 
         thread::sleep(Duration::from_millis(50));
-    };
-
-    let res = run_app(&mut terminal, &mut app);
-
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-
-    if let Err(err) = res {
-        println!("{err:?}");
     }
-
-    Ok(())
 }
 
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
-    let tick_rate = Duration::from_millis(100);
+    let tick_rate = app.config.tick_rate();
     let mut last_tick = Instant::now();
 
     loop {
@@ -111,11 +191,13 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
 
-        if poll(timeout)?
-            && let Event::Key(key) = event::read()?
-            && key.kind == KeyEventKind::Press
-        {
-            handle_key_event(app, key)?;
+        if poll(timeout)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => handle_key_event(app, key)?,
+                Event::Mouse(mouse) => handle_mouse_event(app, mouse)?,
+                Event::Paste(text) => handle_paste(app, &text),
+                _ => {}
+            }
         }
 
         if last_tick.elapsed() >= tick_rate {
@@ -130,8 +212,10 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
             let chunks = ratatui::layout::Layout::default()
                 .direction(ratatui::layout::Direction::Horizontal)
                 .constraints([
-                    ratatui::layout::Constraint::Percentage(40),
-                    ratatui::layout::Constraint::Percentage(60),
+                    ratatui::layout::Constraint::Percentage(app.config.layout.list_width_percent),
+                    ratatui::layout::Constraint::Percentage(
+                        100 - app.config.layout.list_width_percent,
+                    ),
                 ])
                 .split(rect);
 
@@ -141,6 +225,10 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
             // Update mock update progress
             app.update_mock_progress();
 
+            // Apply the background reload once it has finished, and start the next one when due
+            app.poll_background_reload();
+            app.auto_refresh();
+
             last_tick = Instant::now();
         }
 
@@ -0,0 +1,84 @@
+use crate::data_store;
+use crate::entities::package_info::PackageInfo;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const NOTES_FILE_NAME: &str = "notes.json";
+
+/// Personal notes about packages, such as why they were installed, keyed by package name
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct NoteStore {
+    notes: BTreeMap<String, String>,
+}
+
+impl NoteStore {
+    /// Loads the notes, or none if there is no file yet
+    pub fn load() -> Result<Self> {
+        data_store::load_json(NOTES_FILE_NAME)
+    }
+
+    /// Persists the notes
+    pub fn save(&self) -> Result<()> {
+        data_store::save_json(NOTES_FILE_NAME, self)
+    }
+
+    /// Gets the note of a package
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.notes.get(name).map(String::as_str)
+    }
+
+    /// Sets the note of a package. A blank note removes it.
+    pub fn set(&mut self, name: &str, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.notes.remove(name);
+        } else {
+            self.notes.insert(name.to_string(), note.to_string());
+        }
+    }
+
+    /// Copies the notes onto the packages they belong to
+    pub fn annotate(&self, packages: &mut [PackageInfo]) {
+        for package in packages {
+            package.note = self.get(&package.name).map(str::to_string);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_annotate() {
+        let mut notes = NoteStore::default();
+        notes.set("libpq", "  needed by the psycopg build ");
+        notes.set("ffmpeg", "video thumbnails");
+        notes.set("ffmpeg", "   ");
+
+        let mut packages = vec![
+            PackageInfo::fixture("libpq"),
+            PackageInfo::fixture("ffmpeg"),
+        ];
+        packages[1].note = Some("stale".to_string());
+        notes.annotate(&mut packages);
+
+        assert_eq!(
+            packages[0].note.as_deref(),
+            Some("needed by the psycopg build")
+        );
+        assert_eq!(packages[1].note, None);
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let mut notes = NoteStore::default();
+        notes.set("jq", "for shell scripts");
+
+        let json = serde_json::to_string(&notes).unwrap();
+        assert_eq!(json, r#"{"jq":"for shell scripts"}"#);
+        assert_eq!(serde_json::from_str::<NoteStore>(&json).unwrap(), notes);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::data_store;
+use crate::entities::package_info::{PackageInfo, PackageType};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LOG_FILE_NAME: &str = "operations.jsonl";
+
+/// What an operation did to a package
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationKind {
+    Upgrade,
+    Uninstall,
+    Install,
+}
+
+impl OperationKind {
+    /// Gets the past tense shown on the history screen
+    pub fn label(&self) -> &'static str {
+        match self {
+            OperationKind::Upgrade => "upgraded",
+            OperationKind::Uninstall => "uninstalled",
+            OperationKind::Install => "installed",
+        }
+    }
+}
+
+/// One upgrade, uninstall or install, as written to the operation log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationRecord {
+    pub time: u64, // Unix timestamp
+    pub kind: OperationKind,
+    pub package: String,
+    #[serde(default)]
+    pub from_version: Option<String>, // Empty for installs
+    #[serde(default)]
+    pub to_version: Option<String>, // Empty for uninstalls and failed operations
+    pub exit_code: Option<i32>, // Empty if brew could not be run or was killed
+    #[serde(default)]
+    pub output: String, // Everything brew printed, standard output first
+    #[serde(default)]
+    pub removed: Option<RemovedPackage>, // What a successful uninstall removed, to undo it
+}
+
+/// What an uninstall removed, enough to install the package again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemovedPackage {
+    pub name: String,
+    pub package_type: PackageType,
+    pub tap: Option<String>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub options: Vec<String>, // Install options of the removed keg, such as `--HEAD`
+}
+
+impl RemovedPackage {
+    /// Describes an installed package that is about to be removed
+    pub fn from_package(package: &PackageInfo) -> Self {
+        let receipt = package.receipt();
+        let mut options: Vec<String> = receipt
+            .map(|receipt| receipt.used_options.clone())
+            .unwrap_or_default();
+        if receipt.and_then(|receipt| receipt.spec()) == Some("head")
+            && !options.iter().any(|option| option == "--HEAD")
+        {
+            options.push("--HEAD".to_string());
+        }
+
+        Self {
+            name: package.name.clone(),
+            package_type: package.package_type.clone(),
+            tap: package.tap_name().map(str::to_string),
+            version: package.installed_version.clone(),
+            options,
+        }
+    }
+}
+
+impl OperationRecord {
+    /// Creates a record of an operation that just finished
+    pub fn now(kind: OperationKind, package: &str, exit_code: Option<i32>, output: String) -> Self {
+        Self {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            kind,
+            package: package.to_string(),
+            from_version: None,
+            to_version: None,
+            exit_code,
+            output,
+            removed: None,
+        }
+    }
+
+    /// Returns true if brew exited successfully
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// Gets what a successful uninstall removed, so it can be undone
+    pub fn undoable_removal(&self) -> Option<&RemovedPackage> {
+        self.removed.as_ref().filter(|_| self.succeeded())
+    }
+}
+
+/// Adds a record to the end of the operation log
+pub fn append(record: &OperationRecord) -> Result<()> {
+    let path = data_store::data_file(LOG_FILE_NAME)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Loads every record of the operation log, newest first, or none if it can't be read
+pub fn load() -> Vec<OperationRecord> {
+    data_store::data_file(LOG_FILE_NAME)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|contents| parse(&contents))
+        .unwrap_or_default()
+}
+
+/// Parses the lines of an operation log, newest first. Lines that can't be parsed are skipped.
+fn parse(contents: &str) -> Vec<OperationRecord> {
+    let mut records: Vec<OperationRecord> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    records.reverse();
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::install_receipt::{InstallReceipt, KegReceipt};
+
+    #[test]
+    fn test_parse_newest_first() {
+        let mut upgrade = OperationRecord::now(
+            OperationKind::Upgrade,
+            "jq",
+            Some(0),
+            "==> Upgrading jq\n".to_string(),
+        );
+        upgrade.from_version = Some("1.7".to_string());
+        upgrade.to_version = Some("1.7.1".to_string());
+        let uninstall =
+            OperationRecord::now(OperationKind::Uninstall, "wget", Some(1), String::new());
+
+        let contents = format!(
+            "{}\nnot json\n{}\n",
+            serde_json::to_string(&upgrade).unwrap(),
+            serde_json::to_string(&uninstall).unwrap()
+        );
+        let records = parse(&contents);
+
+        assert_eq!(records, vec![uninstall, upgrade]);
+        assert!(records[1].succeeded());
+        assert!(!records[0].succeeded());
+    }
+
+    #[test]
+    fn test_record_format() {
+        let record: OperationRecord = serde_json::from_str(
+            r#"{"time":1700000000,"kind":"install","package":"jq","exit_code":null}"#,
+        )
+        .unwrap();
+        assert_eq!(record.kind, OperationKind::Install);
+        assert_eq!(record.from_version, None);
+        assert!(!record.succeeded());
+        assert_eq!(record.undoable_removal(), None);
+    }
+
+    #[test]
+    fn test_removed_package() {
+        let package = PackageInfo::fixture("neovim")
+            .with_versions("HEAD-abc123", "0.10.0")
+            .with_tap("homebrew/core")
+            .with_kegs(vec![KegReceipt {
+                version: "HEAD-abc123".to_string(),
+                receipt: InstallReceipt::parse(
+                    r#"{"used_options":["--with-lua"],"source":{"spec":"head"}}"#,
+                )
+                .unwrap(),
+            }]);
+        let removed = RemovedPackage::from_package(&package);
+        assert_eq!(removed.options, ["--with-lua", "--HEAD"]);
+        assert_eq!(removed.version.as_deref(), Some("HEAD-abc123"));
+
+        let mut record =
+            OperationRecord::now(OperationKind::Uninstall, "neovim", Some(0), String::new());
+        record.removed = Some(removed.clone());
+        let json = serde_json::to_string(&record).unwrap();
+        let parsed: OperationRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.undoable_removal(), Some(&removed));
+
+        record.exit_code = Some(1);
+        assert_eq!(record.undoable_removal(), None);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::entities::package_info::{PackageInfo, PackageType};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CACHE_FILE_NAME: &str = "packages.json";
+
+/// The last package list loaded from Homebrew, persisted so the next start can render instantly
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackageSnapshot {
+    pub saved_at: u64, // Unix timestamp
+    pub packages: Vec<PackageInfo>,
+}
+
+/// Summary of what changed between two package lists
+#[derive(Debug, Default, PartialEq)]
+pub struct PackageChanges {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub newly_outdated: Vec<String>, // Names of packages with an update available that had none before, sorted
+}
+
+impl PackageChanges {
+    /// Compares two package lists by name, counting packages whose versions or outdated flag differ
+    pub fn between(old: &[PackageInfo], new: &[PackageInfo]) -> Self {
+        let old_by_name: HashMap<&str, &PackageInfo> =
+            old.iter().map(|pkg| (pkg.name.as_str(), pkg)).collect();
+        let new_by_name: HashMap<&str, &PackageInfo> =
+            new.iter().map(|pkg| (pkg.name.as_str(), pkg)).collect();
+
+        let mut changes = Self::default();
+        for (name, new_pkg) in &new_by_name {
+            match old_by_name.get(name) {
+                None => changes.added += 1,
+                Some(old_pkg) => {
+                    if old_pkg.installed_version != new_pkg.installed_version
+                        || old_pkg.current_version != new_pkg.current_version
+                        || old_pkg.outdated != new_pkg.outdated
+                    {
+                        changes.changed += 1;
+                    }
+                    if is_outdated(new_pkg) && !is_outdated(old_pkg) {
+                        changes.newly_outdated.push(name.to_string());
+                    }
+                }
+            }
+        }
+        changes.removed = old_by_name
+            .keys()
+            .filter(|name| !new_by_name.contains_key(*name))
+            .count();
+        changes.newly_outdated.sort();
+
+        changes
+    }
+
+    /// Returns true if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added == 0 && self.removed == 0 && self.changed == 0
+    }
+}
+
+/// Returns true if the package has an update available
+fn is_outdated(package: &PackageInfo) -> bool {
+    package.outdated || package.has_update_available()
+}
+
+/// Gets the path of the package cache file under the user's cache directory
+fn cache_file_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("brew-explorer").join(CACHE_FILE_NAME))
+}
+
+/// Loads the persisted package list, if there is a readable one
+pub fn load() -> Option<PackageSnapshot> {
+    let contents = fs::read_to_string(cache_file_path()?).ok()?;
+    let snapshot: PackageSnapshot = serde_json::from_str(&contents).ok()?;
+
+    if snapshot.packages.is_empty() {
+        None
+    } else {
+        Some(snapshot)
+    }
+}
+
+/// Persists the package list, leaving out the "no packages" and error placeholders
+pub fn save(packages: &[PackageInfo]) -> Result<()> {
+    let path = cache_file_path()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the user's cache directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let snapshot = PackageSnapshot {
+        saved_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        packages: packages
+            .iter()
+            .filter(|pkg| pkg.package_type != PackageType::Unknown)
+            .cloned()
+            .collect(),
+    };
+
+    fs::write(path, serde_json::to_string(&snapshot)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_changes_between() {
+        let old = vec![
+            PackageInfo::fixture("jq").with_versions("1.7.1", "1.7.1"),
+            PackageInfo::fixture("wget").with_versions("1.21", "1.21"),
+            PackageInfo::fixture("curl").with_versions("8.0", "8.0"),
+        ];
+        let new = vec![
+            PackageInfo::fixture("jq").with_versions("1.7.1", "1.7.1"),
+            PackageInfo::fixture("wget").with_versions("1.21", "1.24"),
+            PackageInfo::fixture("ripgrep").with_versions("14.1", "14.1"),
+        ];
+
+        let changes = PackageChanges::between(&old, &new);
+        assert_eq!(
+            changes,
+            PackageChanges {
+                added: 1,
+                removed: 1,
+                changed: 1,
+                newly_outdated: vec!["wget".to_string()],
+            }
+        );
+        assert!(!changes.is_empty());
+    }
+
+    #[test]
+    fn test_package_changes_between_identical_lists() {
+        let packages = vec![PackageInfo::fixture("jq").with_versions("1.7.1", "1.7.1")];
+        assert!(PackageChanges::between(&packages, &packages).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let snapshot = PackageSnapshot {
+            saved_at: 1727000000,
+            packages: vec![PackageInfo::fixture("jq").with_versions("1.7.1", "1.7.1")],
+        };
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: PackageSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.saved_at, 1727000000);
+        assert_eq!(restored.packages[0].name, "jq");
+        assert_eq!(
+            restored.packages[0].installed_version.as_deref(),
+            Some("1.7.1")
+        );
+    }
+}
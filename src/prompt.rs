@@ -0,0 +1,240 @@
+use crate::fuzzy;
+use crate::keymap::Action;
+use crate::line_editor::LineEditor;
+
+/// What the text typed into a prompt is used for
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptKind {
+    SearchName,   // The name to save the current search under
+    Note(String), // The note of the named package
+    ExportPath,   // The file to export the shown packages to
+}
+
+/// A modal asking for one line of text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub title: String,
+    pub input: LineEditor,
+}
+
+impl Prompt {
+    /// Creates a prompt with the given text already typed in
+    pub fn new(kind: PromptKind, title: &str, text: &str) -> Self {
+        let mut input = LineEditor::default();
+        input.set_text(text);
+        Self {
+            kind,
+            title: title.to_string(),
+            input,
+        }
+    }
+}
+
+/// What the item chosen in a picker is used for
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickerKind {
+    SavedSearch,        // Brings back a saved search
+    Collection(String), // Adds the named package to a collection, or removes it
+    Command,            // Runs the action of the item
+}
+
+/// One choice of a picker
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickerItem {
+    pub label: String,  // Matched against the filter, and used to identify the choice
+    pub detail: String, // Shown next to the label
+    pub action: Option<Action>, // The action the command palette runs for the item
+}
+
+/// A modal list of choices that can be narrowed by typing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picker {
+    pub kind: PickerKind,
+    pub title: String,
+    pub filter: LineEditor,
+    items: Vec<PickerItem>,
+    matches: Vec<usize>, // Indices of the items matching the filter, best first
+    selected: usize,     // Index into the rows: the new label if offered, then the matches
+}
+
+impl Picker {
+    /// Creates a picker showing every item
+    pub fn new(kind: PickerKind, title: &str, items: Vec<PickerItem>) -> Self {
+        Self {
+            kind,
+            title: title.to_string(),
+            filter: LineEditor::default(),
+            matches: (0..items.len()).collect(),
+            items,
+            selected: 0,
+        }
+    }
+
+    /// Gets the items matching the filter, best first
+    pub fn matches(&self) -> impl Iterator<Item = &PickerItem> {
+        self.matches.iter().map(|&index| &self.items[index])
+    }
+
+    /// Gets the label a new item would be created with: the filter, when the picker creates
+    /// items and no item is labelled exactly that
+    pub fn new_label(&self) -> Option<String> {
+        let filter = self.filter.text();
+        let label = filter.trim();
+        (matches!(self.kind, PickerKind::Collection(_))
+            && !label.is_empty()
+            && !self.items.iter().any(|item| item.label == label))
+        .then(|| label.to_string())
+    }
+
+    /// Gets the number of rows shown before the matches
+    fn leading_rows(&self) -> usize {
+        usize::from(self.new_label().is_some())
+    }
+
+    /// Gets the position of the selected row, counting the new label first if offered
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Gets the selected item, unless nothing matches or the new label is selected
+    pub fn selected_item(&self) -> Option<&PickerItem> {
+        let position = self.selected.checked_sub(self.leading_rows())?;
+        self.matches.get(position).map(|&index| &self.items[index])
+    }
+
+    /// Gets the label of the selected row, which may be the new label
+    pub fn selected_label(&self) -> Option<String> {
+        match self.new_label() {
+            Some(label) if self.selected == 0 => Some(label),
+            _ => self.selected_item().map(|item| item.label.clone()),
+        }
+    }
+
+    /// Selects the row at the given position, or the last row if there are fewer
+    pub fn select(&mut self, row: usize) {
+        self.selected = 0;
+        self.move_selection(row.try_into().unwrap_or(isize::MAX));
+    }
+
+    /// Moves the selection, stopping at the first and last row
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = (self.leading_rows() + self.matches.len()).saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Edits the filter and narrows the matches to it, selecting the best match
+    pub fn edit_filter(&mut self, edit: impl FnOnce(&mut LineEditor)) {
+        edit(&mut self.filter);
+        let filter = self.filter.text();
+        if filter.trim().is_empty() {
+            self.matches = (0..self.items.len()).collect();
+        } else {
+            let mut ranked: Vec<_> = self
+                .items
+                .iter()
+                .enumerate()
+                .filter_map(|(index, item)| {
+                    fuzzy::match_text(&item.label, &filter)
+                        .map(|(tier, score, _)| (tier, score, index))
+                })
+                .collect();
+            ranked.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+            self.matches = ranked.into_iter().map(|(_, _, index)| index).collect();
+        }
+        self.selected = 0;
+    }
+
+    /// Removes the selected item, returning it
+    pub fn remove_selected(&mut self) -> Option<PickerItem> {
+        let position = self.selected.checked_sub(self.leading_rows())?;
+        let index = *self.matches.get(position)?;
+        let item = self.items.remove(index);
+        self.matches.remove(position);
+        for other in &mut self.matches {
+            if *other > index {
+                *other -= 1;
+            }
+        }
+        self.move_selection(0);
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picker(labels: &[&str]) -> Picker {
+        let items = labels
+            .iter()
+            .map(|label| PickerItem {
+                label: label.to_string(),
+                detail: String::new(),
+                action: None,
+            })
+            .collect();
+        Picker::new(PickerKind::SavedSearch, "Saved Searches", items)
+    }
+
+    fn labels(picker: &Picker) -> Vec<&str> {
+        picker.matches().map(|item| item.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filter_ranks_matches() {
+        let mut picker = picker(&["stale casks", "work tools", "casks"]);
+        assert_eq!(labels(&picker), ["stale casks", "work tools", "casks"]);
+
+        picker.edit_filter(|filter| filter.set_text("cask"));
+        assert_eq!(labels(&picker), ["casks", "stale casks"]);
+
+        picker.edit_filter(|filter| filter.set_text("wt"));
+        assert_eq!(labels(&picker), ["work tools"]);
+
+        picker.edit_filter(LineEditor::clear);
+        assert_eq!(labels(&picker).len(), 3);
+    }
+
+    #[test]
+    fn test_selection_and_removal() {
+        let mut picker = picker(&["a", "b", "c"]);
+        picker.move_selection(5);
+        assert_eq!(picker.selected_item().unwrap().label, "c");
+        picker.move_selection(-1);
+        assert_eq!(picker.remove_selected().unwrap().label, "b");
+        assert_eq!(labels(&picker), ["a", "c"]);
+        assert_eq!(picker.selected_item().unwrap().label, "c");
+
+        picker.remove_selected();
+        picker.remove_selected();
+        assert!(picker.selected_item().is_none());
+        assert!(picker.remove_selected().is_none());
+    }
+
+    #[test]
+    fn test_collection_picker_offers_new_label() {
+        let mut picker = picker(&["devops", "dev"]);
+        picker.kind = PickerKind::Collection("jq".to_string());
+        assert_eq!(picker.new_label(), None);
+
+        // A partial name creates a collection unless a match is chosen
+        picker.edit_filter(|filter| filter.set_text(" devo "));
+        assert_eq!(picker.selected_label().as_deref(), Some("devo"));
+        assert!(picker.selected_item().is_none());
+        picker.move_selection(1);
+        assert_eq!(picker.selected_label().as_deref(), Some("devops"));
+        picker.move_selection(5);
+        assert_eq!(picker.selected_label().as_deref(), Some("devops"));
+
+        // An exact name picks the existing collection
+        picker.edit_filter(|filter| filter.set_text("dev"));
+        assert_eq!(picker.new_label(), None);
+        assert_eq!(picker.selected_label().as_deref(), Some("dev"));
+
+        // Saved searches are never created from the filter
+        let mut picker = self::picker(&["casks"]);
+        picker.edit_filter(|filter| filter.set_text("cas"));
+        assert_eq!(picker.selected_label().as_deref(), Some("casks"));
+    }
+}
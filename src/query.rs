@@ -0,0 +1,271 @@
+use crate::entities::package_info::{PackageInfo, PackageType};
+use anyhow::{Result, bail};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+
+/// A search query: field filters that must all match, and free text to rank the rest by
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub filters: Vec<Filter>,
+    pub text: String, // Free text, matched fuzzily
+}
+
+/// A condition on one field of a package
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Tap(String),         // `tap:homebrew/cask`, matching part of the tap name
+    Outdated(bool),      // `outdated:true`
+    Type(PackageType),   // `type:formula` or `type:cask`
+    Installed(Age),      // `installed:<30d`
+    Description(String), // `desc:"http client"`, matching part of the description
+    Note(String),        // `note:work`, matching part of the personal note
+}
+
+/// How long ago a package must have been installed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Age {
+    Within(u64),    // Installed less than this many seconds ago
+    OlderThan(u64), // Installed more than this many seconds ago
+}
+
+impl Query {
+    /// Parses a query like `tap:homebrew/cask outdated:true installed:<30d desc:"http client" fire`.
+    /// Values with spaces are quoted; words without a field are free text.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut query = Query::default();
+        let mut text = Vec::new();
+
+        for token in tokenize(input)? {
+            match token {
+                Token::Field(field, value) => query.filters.push(Filter::parse(&field, &value)?),
+                Token::Word(word) => text.push(word),
+            }
+        }
+
+        query.text = text.join(" ");
+        Ok(query)
+    }
+
+    /// Checks whether a package passes every field filter
+    pub fn matches(&self, package: &PackageInfo) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        self.filters
+            .iter()
+            .all(|filter| filter.matches(package, now))
+    }
+}
+
+impl Filter {
+    /// Parses the value of a field
+    fn parse(field: &str, value: &str) -> Result<Self> {
+        if value.is_empty() {
+            bail!("{}: needs a value", field);
+        }
+
+        Ok(match field {
+            "tap" => Filter::Tap(value.to_lowercase()),
+            "outdated" => Filter::Outdated(match value {
+                "true" | "yes" => true,
+                "false" | "no" => false,
+                _ => bail!("outdated: expected true or false, got `{}`", value),
+            }),
+            "type" => Filter::Type(match value {
+                "formula" | "formulae" => PackageType::Formulae,
+                "cask" | "casks" => PackageType::Cask,
+                _ => bail!("type: expected formula or cask, got `{}`", value),
+            }),
+            "installed" => Filter::Installed(Age::parse(value)?),
+            "desc" => Filter::Description(value.to_lowercase()),
+            "note" => Filter::Note(value.to_lowercase()),
+            _ => bail!(
+                "unknown field `{}`, expected tap, outdated, type, installed, desc or note",
+                field
+            ),
+        })
+    }
+
+    /// Checks whether a package passes the filter at the given Unix time
+    fn matches(&self, package: &PackageInfo, now: u64) -> bool {
+        match self {
+            Filter::Tap(tap) => package
+                .tap_name()
+                .is_some_and(|name| name.to_lowercase().contains(tap)),
+            Filter::Outdated(outdated) => package.has_update_available() == *outdated,
+            Filter::Type(package_type) => &package.package_type == package_type,
+            Filter::Installed(age) => package
+                .installed_at
+                .is_some_and(|installed_at| age.matches(now.saturating_sub(installed_at))),
+            Filter::Description(text) => package.description.to_lowercase().contains(text),
+            Filter::Note(text) => package
+                .note
+                .as_ref()
+                .is_some_and(|note| note.to_lowercase().contains(text)),
+        }
+    }
+}
+
+impl Age {
+    /// Parses `<` or `>` followed by a number and a unit: h, d, w, m (30 days) or y (365 days)
+    fn parse(value: &str) -> Result<Self> {
+        let (within, amount) = match value.split_at_checked(1) {
+            Some(("<", amount)) => (true, amount),
+            Some((">", amount)) => (false, amount),
+            _ => bail!("installed: expected <N or >N with a unit, like <30d"),
+        };
+        let unit = match amount.chars().last() {
+            Some('h') => HOUR,
+            Some('d') => DAY,
+            Some('w') => 7 * DAY,
+            Some('m') => 30 * DAY,
+            Some('y') => 365 * DAY,
+            _ => bail!("installed: `{}` needs a unit of h, d, w, m or y", amount),
+        };
+        let count: u64 = amount[..amount.len() - 1]
+            .parse()
+            .map_err(|_| anyhow::anyhow!("installed: `{}` is not a number", amount))?;
+
+        let seconds = count
+            .checked_mul(unit)
+            .ok_or_else(|| anyhow::anyhow!("installed: `{}` is too long ago", amount))?;
+        Ok(if within {
+            Age::Within(seconds)
+        } else {
+            Age::OlderThan(seconds)
+        })
+    }
+
+    /// Checks an install age in seconds
+    fn matches(&self, age: u64) -> bool {
+        match self {
+            Age::Within(seconds) => age < *seconds,
+            Age::OlderThan(seconds) => age > *seconds,
+        }
+    }
+}
+
+/// A word of a query
+#[derive(Debug, PartialEq)]
+enum Token {
+    Field(String, String),
+    Word(String),
+}
+
+/// Splits a query on whitespace, keeping quoted values together
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(tokens);
+        }
+
+        let mut field = None;
+        let mut word = String::new();
+        let mut quoted = false;
+        while let Some(c) = chars.next_if(|c| quoted || !c.is_whitespace()) {
+            match c {
+                '"' => quoted = !quoted,
+                ':' if field.is_none() && !quoted => field = Some(std::mem::take(&mut word)),
+                _ => word.push(c),
+            }
+        }
+        if quoted {
+            bail!("missing closing quote");
+        }
+
+        tokens.push(match field {
+            Some(field) => Token::Field(field.to_lowercase(), word),
+            None => Token::Word(word),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fields_and_text() {
+        let query = Query::parse(
+            r#"tap:homebrew/cask outdated:true type:formula installed:<30d desc:"http client" fire fox"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query.filters,
+            vec![
+                Filter::Tap("homebrew/cask".to_string()),
+                Filter::Outdated(true),
+                Filter::Type(PackageType::Formulae),
+                Filter::Installed(Age::Within(30 * DAY)),
+                Filter::Description("http client".to_string()),
+            ]
+        );
+        assert_eq!(query.text, "fire fox");
+    }
+
+    #[test]
+    fn test_plain_text_query() {
+        let query = Query::parse("  jq  ").unwrap();
+        assert!(query.filters.is_empty());
+        assert_eq!(query.text, "jq");
+        assert_eq!(Query::parse("").unwrap(), Query::default());
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        let error = |input: &str| Query::parse(input).unwrap_err().to_string();
+
+        assert!(error("colour:red").contains("unknown field `colour`"));
+        assert!(error("tap:").contains("needs a value"));
+        assert!(error("outdated:maybe").contains("expected true or false"));
+        assert!(error("type:bottle").contains("expected formula or cask"));
+        assert!(error("installed:30d").contains("expected <N or >N"));
+        assert!(error("installed:<30").contains("needs a unit"));
+        assert!(error("installed:>xd").contains("is not a number"));
+        assert!(error(r#"desc:"http"#).contains("missing closing quote"));
+    }
+
+    #[test]
+    fn test_filters_match_packages() {
+        let now = 1_000 * DAY;
+        let httpie = PackageInfo::fixture("httpie").with_description("User-friendly HTTP client");
+        let formula = httpie
+            .clone()
+            .with_tap("homebrew/core")
+            .with_outdated(true)
+            .with_installed_at(now - DAY);
+        let mut cask = httpie
+            .with_type(PackageType::Cask)
+            .with_tap("homebrew/cask (cask)")
+            .with_installed_at(now - 90 * DAY);
+        cask.note = Some("Work laptop only".to_string());
+
+        let matches = |input: &str, package: &PackageInfo| {
+            Query::parse(input)
+                .unwrap()
+                .filters
+                .iter()
+                .all(|filter| filter.matches(package, now))
+        };
+
+        assert!(matches("tap:cask", &cask));
+        assert!(!matches("tap:cask", &formula));
+        assert!(matches("outdated:yes type:formulae", &formula));
+        assert!(!matches("outdated:true", &cask));
+        assert!(matches("installed:<1w", &formula));
+        assert!(matches("installed:>2m", &cask));
+        assert!(!matches("installed:>2m", &formula));
+        assert!(matches(r#"desc:"HTTP client""#, &cask));
+        assert!(!matches("desc:curl", &cask));
+        assert!(matches(r#"note:"work laptop""#, &cask));
+        assert!(!matches("note:work", &formula));
+    }
+}
@@ -1,36 +1,143 @@
 use crate::entities::brew_info_response::BrewInfoResponse;
+use crate::entities::install_receipt::InstallReceipt;
 use crate::entities::package_info::{PackageInfo, PackageType};
 use crate::helpers;
 use anyhow::Result;
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// How a brew command that changes packages exited, and everything it printed
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrewOutput {
+    pub exit_code: Option<i32>, // Empty if brew could not be run or was killed
+    pub output: String,         // Standard output followed by standard error
+}
+
+impl BrewOutput {
+    /// Runs brew with the given arguments, capturing its output
+    fn run(args: &[&str]) -> Self {
+        match Command::new("brew").args(args).output() {
+            Ok(output) => Self {
+                exit_code: output.status.code(),
+                output: format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+            },
+            Err(e) => Self {
+                exit_code: None,
+                output: format!("Could not run brew: {}", e),
+            },
+        }
+    }
+
+    /// Returns true if brew exited successfully
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// Gets the last line brew printed, which explains why it failed
+    pub fn last_line(&self) -> &str {
+        self.output
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("no output")
+    }
+}
+
+/// Taps Homebrew provides without tapping them
+const BUILT_IN_TAPS: [&str; 2] = ["homebrew/core", "homebrew/cask"];
+
+/// Sizes of the kegs measured so far, by formula name and version. Homebrew never changes
+/// an installed keg, so a keg is only walked once however often the packages are reloaded.
+pub type KegSizes = Arc<Mutex<HashMap<(String, String), u64>>>;
+
+/// Where Homebrew keeps formulae and casks, asked for once as it doesn't change while running
+static CELLAR: LazyLock<Option<PathBuf>> = LazyLock::new(|| helpers::brew_cellar().ok());
+static CASKROOM: LazyLock<Option<PathBuf>> = LazyLock::new(|| helpers::brew_caskroom().ok());
 
 pub struct HomebrewRepository {
     installed_packages: Arc<Mutex<Vec<PackageInfo>>>,
     cache: Arc<Mutex<HashMap<String, PackageInfo>>>,
     uninstalled_packages: Arc<Mutex<HashMap<String, Instant>>>, // Track recently uninstalled packages
+    keg_sizes: KegSizes, // Carried over to the repositories of later reloads
 }
 
 impl HomebrewRepository {
     pub fn new() -> Self {
-        let installed_packages = Self::load_installed_packages();
+        let keg_sizes = KegSizes::default();
+        let installed_packages = Self::load_installed_packages(&keg_sizes);
         let cache = Arc::new(Mutex::new(HashMap::new()));
 
         Self {
             installed_packages: Arc::new(Mutex::new(installed_packages)),
             cache,
             uninstalled_packages: Arc::new(Mutex::new(HashMap::new())),
+            keg_sizes,
         }
     }
 
+    /// Creates a repository from an already loaded package list, such as the persisted one,
+    /// without calling Homebrew. The keg sizes it holds are not measured again.
+    pub fn from_packages(packages: Vec<PackageInfo>) -> Self {
+        let keg_sizes = KegSizes::default();
+        Self::remember_installed_sizes(&keg_sizes, &packages);
+        Self::from_installed(packages, keg_sizes)
+    }
+
+    /// Creates a repository from freshly fetched packages, showing a placeholder if there are
+    /// none, keeping the keg sizes measured for them
+    pub fn from_installed(mut packages: Vec<PackageInfo>, keg_sizes: KegSizes) -> Self {
+        if packages.is_empty() {
+            packages.push(Self::create_no_packages_placeholder());
+        }
+        Self {
+            installed_packages: Arc::new(Mutex::new(packages)),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            uninstalled_packages: Arc::new(Mutex::new(HashMap::new())),
+            keg_sizes,
+        }
+    }
+
+    /// Gets the keg sizes measured so far, to pass on to the next reload
+    pub fn keg_sizes(&self) -> KegSizes {
+        Arc::clone(&self.keg_sizes)
+    }
+
+    /// Fetches all directly installed packages from Homebrew, without the placeholders shown in
+    /// the TUI, along with the names of every installed formula and cask, including formulae
+    /// installed as dependencies
+    pub fn fetch_installed_packages(
+        keg_sizes: &KegSizes,
+    ) -> Result<(Vec<PackageInfo>, HashSet<String>)> {
+        let brew_response = helpers::brew_info_all_installed()?;
+        let names = Self::installed_names(&brew_response);
+        Ok((Self::process_brew_response(brew_response, keg_sizes), names))
+    }
+
+    /// Gets the names of every installed formula and cask in a BrewInfoResponse
+    fn installed_names(brew_response: &BrewInfoResponse) -> HashSet<String> {
+        let formulae = brew_response
+            .formulae
+            .iter()
+            .filter(|formula| !formula.installed.is_empty())
+            .map(|formula| formula.name.clone());
+        let casks = brew_response.casks.iter().map(|cask| cask.token.clone());
+        formulae.chain(casks).collect()
+    }
+
     /// Load all installed packages from Homebrew
-    fn load_installed_packages() -> Vec<PackageInfo> {
+    fn load_installed_packages(keg_sizes: &KegSizes) -> Vec<PackageInfo> {
         match helpers::brew_info_all_installed() {
             Ok(brew_response) => {
-                let mut packages = Self::process_brew_response(brew_response);
+                let mut packages = Self::process_brew_response(brew_response, keg_sizes);
 
                 // If no packages are found, show a helpful message
                 if packages.is_empty() {
@@ -43,7 +150,10 @@ impl HomebrewRepository {
     }
 
     /// Process a BrewInfoResponse and return a list of directly installed packages
-    fn process_brew_response(brew_response: BrewInfoResponse) -> Vec<PackageInfo> {
+    fn process_brew_response(
+        brew_response: BrewInfoResponse,
+        keg_sizes: &KegSizes,
+    ) -> Vec<PackageInfo> {
         let mut packages = Vec::new();
 
         // Process formulae - only include packages installed directly (not as dependencies)
@@ -54,23 +164,102 @@ impl HomebrewRepository {
             if !is_directly_installed {
                 continue;
             }
-            packages.push(PackageInfo::from(&formula));
+            let mut package = PackageInfo::from(&formula);
+            Self::fill_keg_details(&mut package, keg_sizes);
+            packages.push(package);
         }
 
         // Process casks
         for cask in brew_response.casks {
-            packages.push(PackageInfo::from(&cask));
+            let mut package = PackageInfo::from(&cask);
+            Self::fill_cask_install_time(&mut package, CASKROOM.as_deref());
+            packages.push(package);
         }
 
         packages
     }
 
+    /// Reads the receipts and size of a formula's kegs in the Cellar
+    fn fill_keg_details(package: &mut PackageInfo, keg_sizes: &KegSizes) {
+        let Some(cellar) = CELLAR.as_deref() else {
+            return;
+        };
+        package.kegs = InstallReceipt::read_kegs(cellar, &package.name);
+        Self::fill_install_time_from_receipt(package);
+        Self::fill_installed_size(package, cellar, keg_sizes);
+    }
+
+    /// Takes the install time from the receipt of the installed keg, as the timeline does
+    fn fill_install_time_from_receipt(package: &mut PackageInfo) {
+        let receipt_time = package
+            .kegs
+            .iter()
+            .find(|keg| Some(&keg.version) == package.installed_version.as_ref())
+            .and_then(|keg| keg.receipt.time);
+        if receipt_time.is_some() {
+            package.installed_at = receipt_time;
+        }
+    }
+
+    /// Measures the installed keg of a formula in the Cellar, unless it was measured before
+    fn fill_installed_size(package: &mut PackageInfo, cellar: &Path, keg_sizes: &KegSizes) {
+        let Some(version) = &package.installed_version else {
+            return;
+        };
+        let key = (package.name.clone(), version.clone());
+        let known = keg_sizes
+            .lock()
+            .ok()
+            .and_then(|sizes| sizes.get(&key).copied());
+
+        // Walk the keg without holding the lock, so other loads aren't kept waiting
+        package.installed_size = known.or_else(|| {
+            let size = helpers::directory_size(&cellar.join(&package.name).join(version))?;
+            if let Ok(mut sizes) = keg_sizes.lock() {
+                sizes.insert(key, size);
+            }
+            Some(size)
+        });
+    }
+
+    /// Remembers the keg sizes of an earlier package list, so later loads don't measure them again
+    fn remember_installed_sizes(keg_sizes: &KegSizes, packages: &[PackageInfo]) {
+        let Ok(mut sizes) = keg_sizes.lock() else {
+            return;
+        };
+        for package in packages {
+            if let (Some(version), Some(size)) =
+                (&package.installed_version, package.installed_size)
+            {
+                sizes.insert((package.name.clone(), version.clone()), size);
+            }
+        }
+    }
+
+    /// Falls back to the timestamp of the cask's Caskroom version directory
+    /// when Homebrew did not report an install time for it
+    fn fill_cask_install_time(package: &mut PackageInfo, caskroom: Option<&Path>) {
+        if package.installed_at.is_some() {
+            return;
+        }
+
+        let (Some(caskroom), Some(version)) = (caskroom, package.installed_version.as_ref()) else {
+            return;
+        };
+
+        package.installed_at = fs::metadata(caskroom.join(&package.name).join(version))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs());
+    }
+
     /// Create a placeholder for when no packages are installed
     fn create_no_packages_placeholder() -> PackageInfo {
         PackageInfo::new(
             "no-packages".to_string(),
             "No packages are currently installed via Homebrew. Use 'brew install <package>' to install packages.".to_string(),
-            "https://brew.sh".to_string(),
+            Some("https://brew.sh".to_string()),
             "1.0.0".to_string(),
             None,
             PackageType::Unknown,
@@ -89,7 +278,7 @@ impl HomebrewRepository {
                 "Error loading packages from Homebrew: {}. Make sure Homebrew is installed and accessible.",
                 err
             ),
-            "https://brew.sh".to_string(),
+            Some("https://brew.sh".to_string()),
             "1.0.0".to_string(),
             None,
             PackageType::Unknown,
@@ -133,33 +322,59 @@ impl HomebrewRepository {
     }
 
     /// Uninstall a package by name
-    pub fn uninstall_package(&self, package_name: &str) -> Result<()> {
-        let output = Command::new("brew")
-            .args(["uninstall", package_name])
-            .output()?;
+    pub fn uninstall_package(&self, package_name: &str) -> BrewOutput {
+        BrewOutput::run(&["uninstall", package_name])
+    }
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!(
-                "Failed to uninstall {}: {}",
-                package_name,
-                error_msg
-            ));
+    /// Update a package by name
+    pub fn update_package(&self, package_name: &str) -> BrewOutput {
+        BrewOutput::run(&["upgrade", package_name])
+    }
+
+    /// Installs a package with the given options, tapping its tap first.
+    /// Homebrew installs the latest version.
+    pub fn install_package(
+        &self,
+        package_name: &str,
+        package_type: &PackageType,
+        tap: Option<&str>,
+        options: &[String],
+    ) -> BrewOutput {
+        let mut tap_output = String::new();
+        if let Some(tap) = tap
+            && !BUILT_IN_TAPS.contains(&tap)
+        {
+            let tapped = BrewOutput::run(&["tap", tap]);
+            if !tapped.success() {
+                return tapped;
+            }
+            tap_output = tapped.output;
         }
 
-        Ok(())
+        let mut args = vec!["install"];
+        if *package_type == PackageType::Cask {
+            args.push("--cask");
+        }
+        args.push(package_name);
+        args.extend(options.iter().map(String::as_str));
+
+        let mut installed = BrewOutput::run(&args);
+        installed.output.insert_str(0, &tap_output);
+        installed
     }
 
-    /// Update a package by name
-    pub fn update_package(&self, package_name: &str) -> Result<()> {
+    /// Pins a formula so `brew upgrade` leaves it alone, or unpins it
+    pub fn pin_package(&self, package_name: &str, pin: bool) -> Result<()> {
+        let command = if pin { "pin" } else { "unpin" };
         let output = Command::new("brew")
-            .args(["upgrade", package_name])
+            .args([command, package_name])
             .output()?;
 
         if !output.status.success() {
             let error_msg = String::from_utf8_lossy(&output.stderr);
             return Err(anyhow::anyhow!(
-                "Failed to update {}: {}",
+                "Failed to {} {}: {}",
+                command,
                 package_name,
                 error_msg
             ));
@@ -221,12 +436,10 @@ impl HomebrewRepository {
                         .unwrap_or_else(|| "unknown".to_string())
                 });
 
-                let package_info = PackageInfo::new(
+                let mut package_info = PackageInfo::new(
                     formula.name.clone(),
                     formula.desc,
-                    formula
-                        .homepage
-                        .unwrap_or_else(|| "No homepage available".to_string()),
+                    formula.homepage,
                     current_version,
                     installed_version,
                     PackageType::Formulae,
@@ -235,6 +448,9 @@ impl HomebrewRepository {
                     formula.caveats,
                     installed_at,
                 );
+                package_info.deprecated = formula.deprecated || formula.disabled;
+                package_info.pinned = formula.pinned;
+                Self::fill_keg_details(&mut package_info, &self.keg_sizes);
 
                 return Ok(Some(package_info));
             }
@@ -248,19 +464,20 @@ impl HomebrewRepository {
                     .desc
                     .unwrap_or_else(|| format!("{} (Cask application)", display_name));
 
-                let package_info = PackageInfo::new(
+                let mut package_info = PackageInfo::new(
                     cask.token,
                     description,
-                    cask.homepage
-                        .unwrap_or_else(|| "No homepage available".to_string()),
+                    cask.homepage,
                     cask.version.clone(),
                     cask.installed.clone(),
                     PackageType::Cask,
                     cask.tap,
                     cask.outdated,
                     cask.caveats,
-                    None, // Casks don't have installation timestamp in the JSON
+                    cask.installed_time,
                 );
+                package_info.deprecated = cask.deprecated || cask.disabled;
+                Self::fill_cask_install_time(&mut package_info, CASKROOM.as_deref());
 
                 return Ok(Some(package_info));
             }
@@ -283,22 +500,102 @@ impl HomebrewRepository {
             uninstalled.insert(package_name.to_string(), now);
         }
     }
+}
 
-    /// Refresh all packages information from Homebrew
-    pub fn refresh_all_packages(&self) -> Result<()> {
-        // Reload all installed packages from brew
-        let new_packages = Self::load_installed_packages();
-
-        // Update the installed packages list
-        if let Ok(mut installed_guard) = self.installed_packages.lock() {
-            *installed_guard = new_packages;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_installed_size_is_measured_once_per_keg() {
+        let cellar =
+            std::env::temp_dir().join(format!("brew-explorer-cellar-{}", std::process::id()));
+        let keg = cellar.join("size-test").join("1.0.0");
+        fs::create_dir_all(&keg).unwrap();
+        fs::write(keg.join("tool"), vec![0u8; 100]).unwrap();
+
+        // A persisted size is used without walking the keg
+        let persisted = HomebrewRepository::from_packages(vec![
+            PackageInfo::fixture("size-test")
+                .with_versions("2.0.0", "2.0.0")
+                .with_installed_size(42),
+        ]);
+        let keg_sizes = persisted.keg_sizes();
+
+        let mut package = PackageInfo::fixture("size-test");
+        HomebrewRepository::fill_installed_size(&mut package, &cellar, &keg_sizes);
+        fs::write(keg.join("tool"), vec![0u8; 300]).unwrap();
+        let mut reloaded = PackageInfo::fixture("size-test");
+        HomebrewRepository::fill_installed_size(&mut reloaded, &cellar, &keg_sizes);
+        let mut upgraded = PackageInfo::fixture("size-test").with_versions("2.0.0", "2.0.0");
+        HomebrewRepository::fill_installed_size(&mut upgraded, &cellar, &keg_sizes);
+        fs::remove_dir_all(&cellar).unwrap();
+
+        assert_eq!(package.installed_size, Some(100));
+        assert_eq!(reloaded.installed_size, Some(100));
+        assert_eq!(upgraded.installed_size, Some(42));
+    }
 
-        // Clear the uninstalled packages blacklist since we have fresh data
-        if let Ok(mut uninstalled) = self.uninstalled_packages.lock() {
-            uninstalled.clear();
-        }
+    #[test]
+    fn test_cask_install_time_falls_back_to_caskroom() {
+        let caskroom =
+            std::env::temp_dir().join(format!("brew-explorer-caskroom-{}", std::process::id()));
+        let version_dir = caskroom.join("firefox").join("130.0");
+        fs::create_dir_all(&version_dir).unwrap();
+        let modified = fs::metadata(&version_dir)
+            .unwrap()
+            .modified()
+            .unwrap()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let cask = PackageInfo::fixture("firefox")
+            .with_type(PackageType::Cask)
+            .with_versions("130.0", "130.0");
+
+        let mut unreported = cask.clone();
+        HomebrewRepository::fill_cask_install_time(&mut unreported, Some(&caskroom));
+        let mut reported = cask.clone().with_installed_at(1727000000);
+        HomebrewRepository::fill_cask_install_time(&mut reported, Some(&caskroom));
+        let mut missing = cask.clone().with_versions("129.0", "130.0");
+        HomebrewRepository::fill_cask_install_time(&mut missing, Some(&caskroom));
+        let mut no_caskroom = cask;
+        HomebrewRepository::fill_cask_install_time(&mut no_caskroom, None);
+        fs::remove_dir_all(&caskroom).unwrap();
+
+        assert_eq!(unreported.installed_at, Some(modified));
+        assert_eq!(reported.installed_at, Some(1727000000));
+        assert_eq!(missing.installed_at, None);
+        assert_eq!(no_caskroom.installed_at, None);
+    }
 
-        Ok(())
+    #[test]
+    fn test_install_time_comes_from_the_installed_keg_receipt() {
+        use crate::entities::install_receipt::KegReceipt;
+
+        let keg = |version: &str, time| KegReceipt {
+            version: version.to_string(),
+            receipt: InstallReceipt {
+                time,
+                ..Default::default()
+            },
+        };
+        let jq = PackageInfo::fixture("jq")
+            .with_versions("1.7.1", "1.7.1")
+            .with_installed_at(1600000000);
+
+        let mut upgraded = jq.clone().with_kegs(vec![
+            keg("1.6", Some(1700000000)),
+            keg("1.7.1", Some(1727000000)),
+        ]);
+        HomebrewRepository::fill_install_time_from_receipt(&mut upgraded);
+        let mut without_time = jq.clone().with_kegs(vec![keg("1.7.1", None)]);
+        HomebrewRepository::fill_install_time_from_receipt(&mut without_time);
+        let mut without_receipt = jq;
+        HomebrewRepository::fill_install_time_from_receipt(&mut without_receipt);
+
+        assert_eq!(upgraded.installed_at, Some(1727000000));
+        assert_eq!(without_time.installed_at, Some(1600000000));
+        assert_eq!(without_receipt.installed_at, Some(1600000000));
     }
 }
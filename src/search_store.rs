@@ -0,0 +1,177 @@
+use crate::data_store;
+use crate::filters::QuickFilters;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+const SEARCHES_FILE_NAME: &str = "searches.json";
+const HISTORY_LIMIT: usize = 100;
+
+/// A search kept under a name, with the quick filters that were active when it was saved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub filters: QuickFilters,
+}
+
+impl SavedSearch {
+    /// Describes the query and filters in one line, for the list of saved searches
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = self
+            .filters
+            .labels()
+            .into_iter()
+            .map(|label| format!("[{}]", label))
+            .collect();
+        if !self.query.is_empty() {
+            parts.insert(0, self.query.clone());
+        }
+        parts.join(" ")
+    }
+}
+
+/// Submitted search queries, saved searches and submitted command lines, remembered between
+/// sessions
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchStore {
+    pub history: Vec<String>, // Oldest first, without duplicates
+    pub saved: Vec<SavedSearch>,
+    pub commands: Vec<String>, // Oldest first, without duplicates
+}
+
+impl SearchStore {
+    /// Loads the stored searches, or none if there is no file yet
+    pub fn load() -> Result<Self> {
+        data_store::load_json(SEARCHES_FILE_NAME)
+    }
+
+    /// Persists the searches
+    pub fn save(&self) -> Result<()> {
+        data_store::save_json(SEARCHES_FILE_NAME, self)
+    }
+
+    /// Adds a submitted query to the history, moving it to the end if it was there already
+    pub fn record(&mut self, query: &str) {
+        remember(&mut self.history, query);
+    }
+
+    /// Adds a submitted command line to its history, moving it to the end if it was there already
+    pub fn record_command(&mut self, command: &str) {
+        remember(&mut self.commands, command);
+    }
+
+    /// Saves a search, replacing any saved search with the same name
+    pub fn save_search(&mut self, search: SavedSearch) {
+        match self
+            .saved
+            .iter_mut()
+            .find(|saved| saved.name == search.name)
+        {
+            Some(saved) => *saved = search,
+            None => {
+                self.saved.push(search);
+                self.saved.sort_by_key(|saved| saved.name.to_lowercase());
+            }
+        }
+    }
+
+    /// Removes the saved search with the given name
+    pub fn remove_saved(&mut self, name: &str) {
+        self.saved.retain(|saved| saved.name != name);
+    }
+
+    /// Finds a saved search by name
+    pub fn find_saved(&self, name: &str) -> Option<&SavedSearch> {
+        self.saved.iter().find(|saved| saved.name == name)
+    }
+}
+
+/// Adds an entry to the end of a history, dropping its earlier copy and the oldest entries
+/// beyond the limit
+fn remember(history: &mut Vec<String>, entry: &str) {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return;
+    }
+    history.retain(|existing| existing != entry);
+    history.push(entry.to_string());
+    if history.len() > HISTORY_LIMIT {
+        history.drain(..history.len() - HISTORY_LIMIT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_moves_repeats_to_the_end() {
+        let mut store = SearchStore::default();
+        store.record("jq");
+        store.record("  type:cask ");
+        store.record("");
+        store.record("jq");
+        assert_eq!(store.history, ["type:cask", "jq"]);
+
+        for i in 0..HISTORY_LIMIT {
+            store.record(&i.to_string());
+        }
+        assert_eq!(store.history.len(), HISTORY_LIMIT);
+        assert_eq!(store.history[0], "0");
+    }
+
+    #[test]
+    fn test_saved_searches() {
+        let mut store = SearchStore::default();
+        let stale_casks = SavedSearch {
+            name: "stale casks".to_string(),
+            query: "installed:>1y".to_string(),
+            filters: QuickFilters {
+                casks_only: true,
+                ..Default::default()
+            },
+        };
+        store.save_search(stale_casks.clone());
+        store.save_search(SavedSearch {
+            name: "Work tools".to_string(),
+            query: "git".to_string(),
+            filters: QuickFilters::default(),
+        });
+        store.save_search(SavedSearch {
+            query: "installed:>2y".to_string(),
+            ..stale_casks
+        });
+
+        let names: Vec<&str> = store.saved.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["stale casks", "Work tools"]);
+        let saved = store.find_saved("stale casks").unwrap();
+        assert_eq!(saved.summary(), "installed:>2y [casks]");
+
+        store.remove_saved("Work tools");
+        assert_eq!(store.saved.len(), 1);
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let mut store = SearchStore::default();
+        store.record("wget");
+        store.record_command("sort date");
+        store.save_search(SavedSearch {
+            name: "outdated".to_string(),
+            query: String::new(),
+            filters: QuickFilters {
+                outdated_only: true,
+                ..Default::default()
+            },
+        });
+
+        let json = serde_json::to_string(&store).unwrap();
+        assert_eq!(serde_json::from_str::<SearchStore>(&json).unwrap(), store);
+        assert_eq!(
+            serde_json::from_str::<SearchStore>("{}").unwrap(),
+            SearchStore::default()
+        );
+    }
+}
@@ -0,0 +1,463 @@
+use crate::data_store;
+use crate::entities::package_info::{PackageInfo, PackageType};
+use crate::export::InventoryRecord;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SNAPSHOTS_FILE_NAME: &str = "snapshots.json";
+
+/// One package of an installed set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub name: String,
+    pub package_type: PackageType,
+    pub tap: Option<String>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl SnapshotEntry {
+    /// Describes an installed package
+    pub fn from_package(package: &PackageInfo) -> Self {
+        Self {
+            name: package.name.clone(),
+            package_type: package.package_type.clone(),
+            tap: package.tap_name().map(str::to_string),
+            version: package.installed_version.clone(),
+            pinned: package.pinned,
+        }
+    }
+
+    /// Formats the entry as `name (type) version`
+    pub fn describe(&self) -> String {
+        format!(
+            "{} ({}) {}",
+            self.name,
+            self.package_type.label(),
+            self.version.as_deref().unwrap_or("-")
+        )
+    }
+}
+
+impl From<&InventoryRecord> for SnapshotEntry {
+    fn from(record: &InventoryRecord) -> Self {
+        Self {
+            name: record.name.clone(),
+            package_type: match record.package_type.as_str() {
+                "formula" => PackageType::Formulae,
+                "cask" => PackageType::Cask,
+                _ => PackageType::Unknown,
+            },
+            tap: record.tap.clone(),
+            version: record.installed_version.clone(),
+            pinned: false, // Not part of the inventory format
+        }
+    }
+}
+
+/// The installed set at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub taken_at: u64, // Unix timestamp
+    pub packages: Vec<SnapshotEntry>,
+}
+
+/// Named snapshots of the installed set, keyed by name
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SnapshotStore {
+    snapshots: BTreeMap<String, Snapshot>,
+}
+
+impl SnapshotStore {
+    /// Loads the snapshots, or none if there is no file yet
+    pub fn load() -> Result<Self> {
+        data_store::load_json(SNAPSHOTS_FILE_NAME)
+    }
+
+    /// Persists the snapshots
+    pub fn save(&self) -> Result<()> {
+        data_store::save_json(SNAPSHOTS_FILE_NAME, self)
+    }
+
+    /// Gets the snapshots by name, sorted
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Snapshot)> {
+        self.snapshots.iter()
+    }
+
+    /// Gets a snapshot by name
+    pub fn get(&self, name: &str) -> Option<&Snapshot> {
+        self.snapshots.get(name)
+    }
+
+    /// Takes a snapshot of the given packages, replacing any snapshot with the same name
+    pub fn take(&mut self, name: &str, packages: &[PackageInfo]) -> &Snapshot {
+        let snapshot = Snapshot {
+            taken_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            packages: packages
+                .iter()
+                .filter(|pkg| pkg.package_type != PackageType::Unknown)
+                .map(SnapshotEntry::from_package)
+                .collect(),
+        };
+        self.snapshots.insert(name.to_string(), snapshot);
+        &self.snapshots[name]
+    }
+
+    /// Removes a snapshot. Returns false if there was none with that name.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.snapshots.remove(name).is_some()
+    }
+}
+
+/// How one installed set differs from another, each part sorted by name
+#[derive(Debug, Default, PartialEq)]
+pub struct PackageSetDiff {
+    pub removed: Vec<SnapshotEntry>, // Only in the old set
+    pub added: Vec<SnapshotEntry>,   // Only in the new set
+    pub changed: Vec<(SnapshotEntry, SnapshotEntry)>, // In both, at different versions
+    pub dependencies: Vec<SnapshotEntry>, // Only in the old set, but installed as a dependency
+}
+
+impl PackageSetDiff {
+    /// Compares two installed sets by package name
+    pub fn between(old: &[SnapshotEntry], new: &[SnapshotEntry]) -> Self {
+        let old_by_name: HashMap<&str, &SnapshotEntry> = old
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry))
+            .collect();
+        let new_by_name: HashMap<&str, &SnapshotEntry> = new
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry))
+            .collect();
+
+        let mut diff = Self {
+            removed: old
+                .iter()
+                .filter(|entry| !new_by_name.contains_key(entry.name.as_str()))
+                .cloned()
+                .collect(),
+            added: new
+                .iter()
+                .filter(|entry| !old_by_name.contains_key(entry.name.as_str()))
+                .cloned()
+                .collect(),
+            changed: old
+                .iter()
+                .filter_map(|entry| {
+                    new_by_name
+                        .get(entry.name.as_str())
+                        .filter(|new_entry| new_entry.version != entry.version)
+                        .map(|new_entry| (entry.clone(), (*new_entry).clone()))
+                })
+                .collect(),
+            dependencies: Vec::new(),
+        };
+        diff.removed.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.added.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.changed.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+        diff
+    }
+
+    /// Moves the packages only in the old set that are installed as a dependency of another
+    /// package out of the removed ones, as they are installed after all
+    pub fn set_aside_dependencies(&mut self, installed_names: &HashSet<String>) {
+        let (dependencies, removed) = std::mem::take(&mut self.removed)
+            .into_iter()
+            .partition(|entry| installed_names.contains(&entry.name));
+        self.removed = removed;
+        self.dependencies = dependencies;
+    }
+
+    /// Returns true if the sets hold the same packages at the same versions
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+            && self.added.is_empty()
+            && self.changed.is_empty()
+            && self.dependencies.is_empty()
+    }
+
+    /// Formats the differences as `-`, `+` and `~` lines under the given headings
+    pub fn render(&self, removed_heading: &str, added_heading: &str) -> String {
+        if self.is_empty() {
+            return "No differences\n".to_string();
+        }
+
+        let mut output = String::new();
+        let mut section = |heading: &str, lines: Vec<String>| {
+            if !lines.is_empty() {
+                output.push_str(&format!("{}:\n", heading));
+                for line in lines {
+                    output.push_str(&format!("  {}\n", line));
+                }
+            }
+        };
+        section(
+            removed_heading,
+            self.removed
+                .iter()
+                .map(|entry| format!("- {}", entry.describe()))
+                .collect(),
+        );
+        section(
+            added_heading,
+            self.added
+                .iter()
+                .map(|entry| format!("+ {}", entry.describe()))
+                .collect(),
+        );
+        section(
+            "Installed only as a dependency",
+            self.dependencies
+                .iter()
+                .map(|entry| format!("= {}", entry.describe()))
+                .collect(),
+        );
+        section(
+            "Different versions",
+            self.changed
+                .iter()
+                .map(|(old, new)| {
+                    format!(
+                        "~ {} {} -> {}",
+                        old.name,
+                        old.version.as_deref().unwrap_or("-"),
+                        new.version.as_deref().unwrap_or("-")
+                    )
+                })
+                .collect(),
+        );
+        output
+    }
+}
+
+/// What restoring a snapshot does to the installed set
+#[derive(Debug, PartialEq)]
+pub struct RestorePlan {
+    pub install: Vec<SnapshotEntry>, // In the snapshot but not installed
+    pub uninstall: Vec<SnapshotEntry>, // Installed since the snapshot
+    pub inexact: Vec<String>,        // Versions Homebrew cannot bring back
+    pub dependencies: Vec<SnapshotEntry>, // In the snapshot, now installed as a dependency
+}
+
+impl RestorePlan {
+    /// Plans how to get from the installed set back to a snapshot. Homebrew only installs the
+    /// latest version of a package, so other versions are reported rather than restored.
+    /// Packages installed as a dependency, named in `installed_names`, are not installed again.
+    pub fn new(
+        snapshot: &Snapshot,
+        installed: &[SnapshotEntry],
+        installed_names: &HashSet<String>,
+    ) -> Self {
+        let mut diff = PackageSetDiff::between(&snapshot.packages, installed);
+        diff.set_aside_dependencies(installed_names);
+
+        let pinned = |entry: &SnapshotEntry| if entry.pinned { "pinned " } else { "" };
+        let mut inexact: Vec<String> = diff
+            .changed
+            .iter()
+            .map(|(old, new)| {
+                format!(
+                    "{} was {}at {} and stays at {}",
+                    old.name,
+                    pinned(old),
+                    old.version.as_deref().unwrap_or("-"),
+                    new.version.as_deref().unwrap_or("-")
+                )
+            })
+            .collect();
+        inexact.extend(
+            diff.removed
+                .iter()
+                .filter(|entry| entry.pinned)
+                .map(|entry| {
+                    format!(
+                        "{} was pinned at {} and is installed at the latest version, then pinned",
+                        entry.name,
+                        entry.version.as_deref().unwrap_or("-")
+                    )
+                }),
+        );
+
+        Self {
+            install: diff.removed,
+            uninstall: diff.added,
+            inexact,
+            dependencies: diff.dependencies,
+        }
+    }
+
+    /// Returns true if the snapshot is already restored as far as Homebrew can
+    pub fn is_empty(&self) -> bool {
+        self.install.is_empty() && self.uninstall.is_empty()
+    }
+
+    /// Formats the plan as `+` lines to install, `-` lines to uninstall and the versions
+    /// that cannot be restored exactly
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        for entry in &self.install {
+            output.push_str(&format!("+ install {}\n", entry.describe()));
+        }
+        for entry in &self.uninstall {
+            output.push_str(&format!("- uninstall {}\n", entry.describe()));
+        }
+        for entry in &self.dependencies {
+            output.push_str(&format!(
+                "= keep {}, installed as a dependency\n",
+                entry.describe()
+            ));
+        }
+        if self.is_empty() {
+            output.push_str("Nothing to install or uninstall\n");
+        }
+        if !self.inexact.is_empty() {
+            output.push_str("Homebrew cannot restore these versions exactly:\n");
+            for line in &self.inexact {
+                output.push_str(&format!("  {}\n", line));
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, version: &str) -> SnapshotEntry {
+        SnapshotEntry {
+            name: name.to_string(),
+            package_type: PackageType::Formulae,
+            tap: Some("homebrew/core".to_string()),
+            version: Some(version.to_string()),
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_diff_between() {
+        let old = vec![
+            entry("wget", "1.21"),
+            entry("jq", "1.6"),
+            entry("curl", "8.0"),
+        ];
+        let new = vec![
+            entry("jq", "1.7.1"),
+            entry("ripgrep", "14.1"),
+            entry("curl", "8.0"),
+        ];
+
+        let diff = PackageSetDiff::between(&old, &new);
+        assert_eq!(diff.removed, [entry("wget", "1.21")]);
+        assert_eq!(diff.added, [entry("ripgrep", "14.1")]);
+        assert_eq!(diff.changed, [(entry("jq", "1.6"), entry("jq", "1.7.1"))]);
+        assert_eq!(
+            diff.render("Only in before", "Only installed"),
+            "Only in before:\n  - wget (formula) 1.21\n\
+             Only installed:\n  + ripgrep (formula) 14.1\n\
+             Different versions:\n  ~ jq 1.6 -> 1.7.1\n"
+        );
+
+        assert!(PackageSetDiff::between(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_dependencies_are_not_missing() {
+        let theirs = vec![entry("yq", "4.44"), entry("jq", "1.7.1")];
+        let ours = vec![entry("yq", "4.44")];
+        let installed_names = HashSet::from(["yq".to_string(), "jq".to_string()]);
+
+        let mut diff = PackageSetDiff::between(&theirs, &ours);
+        diff.set_aside_dependencies(&installed_names);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.dependencies, [entry("jq", "1.7.1")]);
+        assert_eq!(
+            diff.render("Only in laptop.json", "Only installed here"),
+            "Installed only as a dependency:\n  = jq (formula) 1.7.1\n"
+        );
+    }
+
+    #[test]
+    fn test_restore_plan() {
+        let mut pinned_wget = entry("wget", "1.21");
+        pinned_wget.pinned = true;
+        let mut pinned_jq = entry("jq", "1.6");
+        pinned_jq.pinned = true;
+        let snapshot = Snapshot {
+            taken_at: 0,
+            packages: vec![
+                pinned_wget.clone(),
+                pinned_jq,
+                entry("curl", "8.0"),
+                entry("oniguruma", "6.9.9"),
+            ],
+        };
+        let installed = vec![
+            entry("jq", "1.7.1"),
+            entry("curl", "8.0"),
+            entry("helm", "3.0"),
+        ];
+
+        let installed_names = HashSet::from([
+            "jq".to_string(),
+            "curl".to_string(),
+            "helm".to_string(),
+            "oniguruma".to_string(),
+        ]);
+
+        let plan = RestorePlan::new(&snapshot, &installed, &installed_names);
+        assert_eq!(plan.install, [pinned_wget]);
+        assert_eq!(plan.uninstall, [entry("helm", "3.0")]);
+        assert_eq!(plan.dependencies, [entry("oniguruma", "6.9.9")]);
+        assert_eq!(
+            plan.inexact,
+            [
+                "jq was pinned at 1.6 and stays at 1.7.1",
+                "wget was pinned at 1.21 and is installed at the latest version, then pinned"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_inventory_record() {
+        let record = InventoryRecord {
+            name: "firefox".to_string(),
+            package_type: "cask".to_string(),
+            tap: Some("homebrew/cask".to_string()),
+            installed_version: Some("130.0".to_string()),
+            current_version: "131.0".to_string(),
+            outdated: true,
+            installed_at: None,
+            homepage: None,
+            note: None,
+        };
+        let entry = SnapshotEntry::from(&record);
+        assert_eq!(entry.package_type, PackageType::Cask);
+        assert_eq!(entry.describe(), "firefox (cask) 130.0");
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let mut store = SnapshotStore::default();
+        store.snapshots.insert(
+            "before-upgrade".to_string(),
+            Snapshot {
+                taken_at: 1_700_000_000,
+                packages: vec![entry("jq", "1.7.1")],
+            },
+        );
+        assert!(!store.remove("missing"));
+
+        let json = serde_json::to_string(&store).unwrap();
+        assert_eq!(serde_json::from_str::<SnapshotStore>(&json).unwrap(), store);
+        assert!(store.remove("before-upgrade"));
+        assert!(store.get("before-upgrade").is_none());
+    }
+}
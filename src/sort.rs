@@ -0,0 +1,156 @@
+use crate::entities::package_info::{PackageInfo, PackageType};
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+
+/// Orders the package list can be sorted in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    #[default]
+    Name,
+    Type,
+    Tap,
+    InstallDate,
+    OutdatedFirst,
+    Size,
+}
+
+impl SortMode {
+    const CYCLE: [SortMode; 6] = [
+        SortMode::Name,
+        SortMode::Type,
+        SortMode::Tap,
+        SortMode::InstallDate,
+        SortMode::OutdatedFirst,
+        SortMode::Size,
+    ];
+
+    /// Gets a short label for the list title
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortMode::Name => "name",
+            SortMode::Type => "type",
+            SortMode::Tap => "tap",
+            SortMode::InstallDate => "install date",
+            SortMode::OutdatedFirst => "outdated first",
+            SortMode::Size => "size",
+        }
+    }
+
+    /// Gets the next mode in the cycle, skipping size when no package size is known
+    pub fn next(&self, sizes_known: bool) -> SortMode {
+        let index = Self::CYCLE
+            .iter()
+            .position(|mode| mode == self)
+            .unwrap_or(0);
+        let next = Self::CYCLE[(index + 1) % Self::CYCLE.len()];
+        if next == SortMode::Size && !sizes_known {
+            next.next(sizes_known)
+        } else {
+            next
+        }
+    }
+
+    /// Sorts packages in this mode. Ties are broken by name, so the order is always stable.
+    pub fn sort(&self, packages: &mut [PackageInfo]) {
+        packages.sort_by(|a, b| self.compare(a, b).then_with(|| compare_names(a, b)));
+    }
+
+    /// Compares two packages by the key of this mode only
+    fn compare(&self, a: &PackageInfo, b: &PackageInfo) -> Ordering {
+        match self {
+            SortMode::Name => Ordering::Equal,
+            SortMode::Type => type_rank(a).cmp(&type_rank(b)),
+            // Packages without a tap go last
+            SortMode::Tap => (a.tap.is_none(), a.tap_name()).cmp(&(b.tap.is_none(), b.tap_name())),
+            // Newest first, packages without an install time last
+            SortMode::InstallDate => (a.installed_at.is_none(), Reverse(a.installed_at))
+                .cmp(&(b.installed_at.is_none(), Reverse(b.installed_at))),
+            SortMode::OutdatedFirst => b.has_update_available().cmp(&a.has_update_available()),
+            // Largest first, packages of unknown size last
+            SortMode::Size => (a.installed_size.is_none(), Reverse(a.installed_size))
+                .cmp(&(b.installed_size.is_none(), Reverse(b.installed_size))),
+        }
+    }
+}
+
+/// Orders formulae before casks
+fn type_rank(package: &PackageInfo) -> u8 {
+    match package.package_type {
+        PackageType::Formulae => 0,
+        PackageType::Cask => 1,
+        PackageType::Unknown => 2,
+    }
+}
+
+/// Compares package names case-insensitively
+fn compare_names(a: &PackageInfo, b: &PackageInfo) -> Ordering {
+    a.name.to_lowercase().cmp(&b.name.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_packages() -> Vec<PackageInfo> {
+        vec![
+            PackageInfo::fixture("firefox")
+                .with_type(PackageType::Cask)
+                .with_tap("homebrew/cask")
+                .with_installed_at(300),
+            PackageInfo::fixture("wget")
+                .with_versions("1.0.0", "2.0.0")
+                .with_tap("homebrew/core")
+                .with_installed_at(100)
+                .with_installed_size(2_000),
+            PackageInfo::fixture("Jq")
+                .with_tap("homebrew/core")
+                .with_installed_size(500),
+            PackageInfo::fixture("brew-explorer")
+                .with_tap("cosmincatalin/tap")
+                .with_installed_at(200),
+        ]
+    }
+
+    fn sorted_names(mode: SortMode) -> Vec<String> {
+        let mut packages = sample_packages();
+        mode.sort(&mut packages);
+        packages.into_iter().map(|pkg| pkg.name).collect()
+    }
+
+    #[test]
+    fn test_sort_modes() {
+        assert_eq!(
+            sorted_names(SortMode::Name),
+            ["brew-explorer", "firefox", "Jq", "wget"]
+        );
+        assert_eq!(
+            sorted_names(SortMode::Type),
+            ["brew-explorer", "Jq", "wget", "firefox"]
+        );
+        assert_eq!(
+            sorted_names(SortMode::Tap),
+            ["brew-explorer", "firefox", "Jq", "wget"]
+        );
+        assert_eq!(
+            sorted_names(SortMode::InstallDate),
+            ["firefox", "brew-explorer", "wget", "Jq"]
+        );
+        assert_eq!(
+            sorted_names(SortMode::OutdatedFirst),
+            ["wget", "brew-explorer", "firefox", "Jq"]
+        );
+        assert_eq!(
+            sorted_names(SortMode::Size),
+            ["wget", "Jq", "brew-explorer", "firefox"]
+        );
+    }
+
+    #[test]
+    fn test_cycle_skips_unknown_sizes() {
+        assert_eq!(SortMode::Name.next(false), SortMode::Type);
+        assert_eq!(SortMode::OutdatedFirst.next(true), SortMode::Size);
+        assert_eq!(SortMode::OutdatedFirst.next(false), SortMode::Name);
+        assert_eq!(SortMode::Size.next(true), SortMode::Name);
+    }
+}
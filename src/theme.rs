@@ -0,0 +1,317 @@
+use crate::config::{ColourConfig, Config};
+use anyhow::Result;
+use ratatui::style::{Color, Modifier, Style};
+
+/// The names of the built-in themes
+pub const THEME_NAMES: [&str; 4] = ["dark", "light", "solarized", "high-contrast"];
+
+/// Every colour the UI draws with.
+/// A highlight of `Color::Reset` selects with reverse video instead of a background colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub outdated: Color,   // Packages with an update available
+    pub up_to_date: Color, // Packages on the latest version
+    pub highlight: Color,  // Background of the selected item
+    pub status_bar_background: Color,
+    pub status_bar_foreground: Color,
+    pub accent: Color,  // Headings, keys and dates
+    pub warning: Color, // Updates and messages asking the user to wait
+    pub danger: Color,  // Uninstalls and deprecation
+    pub success: Color, // Installs and progress
+    pub muted: Color,   // Hints and secondary text
+    pub faint: Color,   // Text that should barely show
+    pub link: Color,    // Homepage URLs
+    pub modal_border: Color,
+    pub modal_background: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The original colours, for dark terminals
+    pub fn dark() -> Self {
+        Self {
+            outdated: Color::Rgb(220, 80, 80), // Soft reddish colour
+            up_to_date: Color::Green,
+            highlight: Color::Blue,
+            status_bar_background: Color::Blue,
+            status_bar_foreground: Color::White,
+            accent: Color::Cyan,
+            warning: Color::Yellow,
+            danger: Color::Red,
+            success: Color::Green,
+            muted: Color::Gray,
+            faint: Color::DarkGray,
+            link: Color::Blue,
+            modal_border: Color::Blue,
+            modal_background: Color::Black,
+        }
+    }
+
+    /// Darker colours that stay readable on light terminals
+    pub fn light() -> Self {
+        Self {
+            outdated: Color::Rgb(190, 30, 30),
+            up_to_date: Color::Rgb(0, 120, 0),
+            highlight: Color::Rgb(190, 210, 240),
+            status_bar_background: Color::Rgb(40, 90, 160),
+            status_bar_foreground: Color::White,
+            accent: Color::Rgb(0, 110, 140),
+            warning: Color::Rgb(160, 100, 0),
+            danger: Color::Rgb(190, 30, 30),
+            success: Color::Rgb(0, 120, 0),
+            muted: Color::Rgb(100, 100, 100),
+            faint: Color::Rgb(150, 150, 150),
+            link: Color::Rgb(0, 70, 190),
+            modal_border: Color::Rgb(40, 90, 160),
+            modal_background: Color::White,
+        }
+    }
+
+    /// The dark Solarized palette
+    pub fn solarized() -> Self {
+        Self {
+            outdated: Color::Rgb(220, 50, 47),                // red
+            up_to_date: Color::Rgb(133, 153, 0),              // green
+            highlight: Color::Rgb(7, 54, 66),                 // base02
+            status_bar_background: Color::Rgb(7, 54, 66),     // base02
+            status_bar_foreground: Color::Rgb(147, 161, 161), // base1
+            accent: Color::Rgb(42, 161, 152),                 // cyan
+            warning: Color::Rgb(181, 137, 0),                 // yellow
+            danger: Color::Rgb(220, 50, 47),                  // red
+            success: Color::Rgb(133, 153, 0),                 // green
+            muted: Color::Rgb(131, 148, 150),                 // base0
+            faint: Color::Rgb(88, 110, 117),                  // base01
+            link: Color::Rgb(38, 139, 210),                   // blue
+            modal_border: Color::Rgb(38, 139, 210),           // blue
+            modal_background: Color::Rgb(0, 43, 54),          // base03
+        }
+    }
+
+    /// Bright colours and a reverse-video selection
+    pub fn high_contrast() -> Self {
+        Self {
+            outdated: Color::LightRed,
+            up_to_date: Color::LightGreen,
+            highlight: Color::Reset,
+            status_bar_background: Color::White,
+            status_bar_foreground: Color::Black,
+            accent: Color::LightCyan,
+            warning: Color::LightYellow,
+            danger: Color::LightRed,
+            success: Color::LightGreen,
+            muted: Color::White,
+            faint: Color::Gray,
+            link: Color::LightBlue,
+            modal_border: Color::White,
+            modal_background: Color::Black,
+        }
+    }
+
+    /// No colours at all, for terminals with `NO_COLOR` set
+    pub fn monochrome() -> Self {
+        Self {
+            outdated: Color::Reset,
+            up_to_date: Color::Reset,
+            highlight: Color::Reset,
+            status_bar_background: Color::Reset,
+            status_bar_foreground: Color::Reset,
+            accent: Color::Reset,
+            warning: Color::Reset,
+            danger: Color::Reset,
+            success: Color::Reset,
+            muted: Color::Reset,
+            faint: Color::Reset,
+            link: Color::Reset,
+            modal_border: Color::Reset,
+            modal_background: Color::Reset,
+        }
+    }
+
+    /// Looks up a built-in theme by name
+    pub fn built_in(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Builds the theme chosen in the config: a built-in or custom theme,
+    /// with the `[colours]` overrides on top
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let name = config.theme.as_deref().unwrap_or("dark");
+        let mut theme = match config.themes.get(name) {
+            Some(custom) => {
+                // Custom theme bases are checked when the config is validated
+                let mut theme = Self::built_in(&custom.base).unwrap_or_default();
+                theme.apply(&custom.colours);
+                theme
+            }
+            None => Self::built_in(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "theme must be one of {} or a theme defined under [themes], got \"{}\"",
+                    THEME_NAMES.join(", "),
+                    name
+                )
+            })?,
+        };
+        theme.apply(&config.colours);
+        Ok(theme)
+    }
+
+    /// Builds the theme for the terminal, dropping every colour if `NO_COLOR` is set
+    pub fn for_terminal(config: &Config) -> Result<Self> {
+        let theme = Self::from_config(config)?;
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Ok(Self::monochrome());
+        }
+        Ok(theme)
+    }
+
+    /// Replaces the colours set in the overrides
+    fn apply(&mut self, colours: &ColourConfig) {
+        let overrides = [
+            (&mut self.outdated, colours.outdated),
+            (&mut self.up_to_date, colours.up_to_date),
+            (&mut self.highlight, colours.highlight),
+            (
+                &mut self.status_bar_background,
+                colours.status_bar_background,
+            ),
+            (
+                &mut self.status_bar_foreground,
+                colours.status_bar_foreground,
+            ),
+            (&mut self.accent, colours.accent),
+            (&mut self.warning, colours.warning),
+            (&mut self.danger, colours.danger),
+            (&mut self.success, colours.success),
+            (&mut self.muted, colours.muted),
+            (&mut self.faint, colours.faint),
+            (&mut self.link, colours.link),
+            (&mut self.modal_border, colours.modal_border),
+            (&mut self.modal_background, colours.modal_background),
+        ];
+        for (colour, value) in overrides {
+            if let Some(value) = value {
+                *colour = value.0;
+            }
+        }
+    }
+
+    /// Gets the style of the selected item
+    pub fn selection(&self) -> Style {
+        if self.highlight == Color::Reset {
+            Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else {
+            Style::default()
+                .bg(self.highlight)
+                .add_modifier(Modifier::BOLD)
+        }
+    }
+
+    /// Gets the style of a package name, bold for outdated packages when there is no colour to tell them apart
+    pub fn package(&self, outdated: bool) -> Style {
+        if !outdated {
+            Style::default().fg(self.up_to_date)
+        } else if self.outdated == self.up_to_date {
+            Style::default()
+                .fg(self.outdated)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(self.outdated)
+        }
+    }
+
+    /// Gets the style of a modal button drawn in the given colour
+    pub fn button(&self, colour: Color) -> Style {
+        if colour == Color::Reset {
+            Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else {
+            Style::default()
+                .bg(colour)
+                .fg(self.modal_background)
+                .add_modifier(Modifier::BOLD)
+        }
+    }
+
+    /// Gets the style of the status bar
+    pub fn status_bar(&self) -> Style {
+        if self.status_bar_background == Color::Reset {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+                .bg(self.status_bar_background)
+                .fg(self.status_bar_foreground)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_in_themes_by_name() {
+        for name in THEME_NAMES {
+            let config = Config::parse(&format!("theme = \"{}\"", name)).unwrap();
+            assert_eq!(
+                Theme::from_config(&config).unwrap(),
+                Theme::built_in(name).unwrap()
+            );
+        }
+        assert_eq!(
+            Theme::from_config(&Config::default()).unwrap(),
+            Theme::dark()
+        );
+    }
+
+    #[test]
+    fn test_custom_theme_with_overrides() {
+        let config = Config::parse(
+            r##"
+            theme = "mine"
+
+            [themes.mine]
+            base = "light"
+
+            [themes.mine.colours]
+            accent = "magenta"
+
+            [colours]
+            highlight = "#ffff00"
+            "##,
+        )
+        .unwrap();
+
+        let theme = Theme::from_config(&config).unwrap();
+        assert_eq!(theme.accent, Color::Magenta);
+        assert_eq!(theme.highlight, Color::Rgb(255, 255, 0));
+        assert_eq!(theme.up_to_date, Theme::light().up_to_date);
+    }
+
+    #[test]
+    fn test_unknown_theme_is_reported() {
+        let error = Config::parse("theme = \"neon\"").unwrap_err();
+        assert!(error.to_string().contains("got \"neon\""));
+
+        let error = Config::parse("[themes.mine]\nbase = \"neon\"\n").unwrap_err();
+        assert!(error.to_string().contains("themes.mine.base"));
+    }
+
+    #[test]
+    fn test_monochrome_styles_use_modifiers() {
+        let theme = Theme::monochrome();
+        assert!(theme.selection().add_modifier.contains(Modifier::REVERSED));
+        assert!(theme.package(true).add_modifier.contains(Modifier::BOLD));
+        assert!(!theme.package(false).add_modifier.contains(Modifier::BOLD));
+        assert!(theme.status_bar().add_modifier.contains(Modifier::REVERSED));
+    }
+}
@@ -1,15 +1,33 @@
-use crate::app::{App, ModalState, UpdateStage};
+use crate::app::{App, ModalState, MouseTargets, Screen, UpdateStage};
+use crate::entities::timeline_entry::TimelineEvent;
+use crate::fuzzy::{self, PackageMatch};
+use crate::helpers;
+use crate::keymap::{Action, KeyMode, Keymap, format_sequence};
+use crate::line_editor::LineEditor;
+use crate::operation_log::OperationRecord;
+use crate::prompt::{Picker, PickerItem, PickerKind, Prompt};
+use crate::theme::Theme;
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Margin},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position},
+    style::{Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Row, Table, Wrap},
+    widgets::{
+        Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Row, Table, Wrap,
+    },
 };
 use std::time::Duration;
 
+/// The start of the package list title while searching, followed by the query
+const SEARCH_PROMPT: &str = "Packages (Search: ";
+
 /// Renders a fancy loading screen with ASCII art
-pub fn render_loading_screen(f: &mut Frame, loading_dots: usize, elapsed: std::time::Duration) {
+pub fn render_loading_screen(
+    f: &mut Frame,
+    theme: &Theme,
+    loading_dots: usize,
+    elapsed: std::time::Duration,
+) {
     let area = f.area();
 
     // ASCII art for "Brew Explorer"
@@ -47,7 +65,7 @@ pub fn render_loading_screen(f: &mut Frame, loading_dots: usize, elapsed: std::t
             Line::from(Span::styled(
                 *line,
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD),
             ))
         })
@@ -81,20 +99,20 @@ pub fn render_loading_screen(f: &mut Frame, loading_dots: usize, elapsed: std::t
             Span::styled("🍺 ", Style::default()),
             Span::styled(
                 "Loading Homebrew packages",
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme.warning),
             ),
             Span::styled(
                 dots,
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.warning)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(elapsed_display, Style::default().fg(Color::Gray)),
+            Span::styled(elapsed_display, Style::default().fg(theme.muted)),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             "Press 'q' to quit",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.faint),
         )),
     ];
 
@@ -113,19 +131,37 @@ pub fn render_ui(f: &mut Frame, app: &mut App) {
         .constraints([Constraint::Min(0), Constraint::Length(1)])
         .split(f.area());
 
-    // Split the main area horizontally for package list and details
-    let content_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-        .split(main_chunks[0]);
+    // The renderers below record where the mouse can click
+    app.mouse_targets = MouseTargets::default();
 
-    render_package_list(f, app, content_chunks[0]);
-    render_package_details(f, app, content_chunks[1]);
+    if app.screen == Screen::Timeline {
+        render_timeline(f, app, main_chunks[0]);
+    } else if app.screen == Screen::History {
+        render_history(f, app, main_chunks[0]);
+    } else {
+        // Split the main area horizontally for package list and details
+        let content_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(app.config.layout.list_width_percent),
+                Constraint::Percentage(100 - app.config.layout.list_width_percent),
+            ])
+            .split(main_chunks[0]);
+
+        render_package_list(f, app, content_chunks[0]);
+        app.mouse_targets.homepage = render_package_details(f, app, content_chunks[1]);
+    }
     render_status_bar(f, app, main_chunks[1]);
 
     // Render modal if one is open
-    if app.modal_state != ModalState::None {
-        render_modal(f, app);
+    match app.modal_state {
+        ModalState::None => {}
+        ModalState::Help => app.help_rows = render_help_modal(f, app),
+        _ => {
+            let targets = render_modal(f, app);
+            app.mouse_targets.buttons = targets.buttons;
+            app.mouse_targets.rows = targets.rows;
+        }
     }
 }
 
@@ -136,34 +172,57 @@ fn render_package_list(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect
     // Calculate optimal number of columns based on available width
     // Assume minimum 25 characters per package name + 3 characters padding
     let min_column_width = 28;
-    let max_visible_columns = (available_width / min_column_width).clamp(1, 4); // Cap at 4 columns for readability
+    let max_visible_columns =
+        (available_width / min_column_width).clamp(1, app.config.layout.max_columns); // Cap columns for readability
+    let theme = app.theme.clone();
 
-    let total_items = if app.is_searching {
-        app.filtered_items.len()
-    } else {
-        app.items.len()
-    };
+    let total_items = app.get_display_items().len();
 
     // Create title
     let title = if app.is_searching {
         if total_items == 0 {
-            format!("Packages (Search: {}) - No results", app.search_query)
+            format!("{}{}) - No results", SEARCH_PROMPT, app.search.text())
         } else {
-            format!("Packages (Search: {})", app.search_query)
+            format!("{}{})", SEARCH_PROMPT, app.search.text())
         }
+    } else if app.filters.is_active() && total_items == 0 {
+        "Packages - No results".to_string()
     } else {
         "Packages".to_string()
     };
+    let title = app
+        .filters
+        .labels()
+        .iter()
+        .fold(title, |title, label| format!("{} [{}]", title, label));
+    let title = format!("{} - sorted by {}", title, app.sort_mode.label());
+    let title = format!("{} {}", title, refresh_indicator(app));
+
+    // Show the terminal cursor in the search prompt, unless it is cut off or a modal is open
+    if app.is_searching && app.modal_state == ModalState::None {
+        let cursor_x = area.x as usize
+            + 1
+            + Span::raw(SEARCH_PROMPT).width()
+            + Span::raw(app.search.text_before_cursor()).width();
+        if cursor_x < area.right().saturating_sub(1) as usize {
+            f.set_cursor_position(Position::new(cursor_x as u16, area.y));
+        }
+    }
+
+    // An invalid search query is explained along the bottom of the list
+    let mut block = Block::default().borders(Borders::ALL).title(title);
+    if let Some(error) = app.query_error.as_ref().filter(|_| app.is_searching) {
+        block = block.title_bottom(Line::styled(
+            format!(" Invalid query: {} ", error),
+            Style::default().fg(theme.danger),
+        ));
+    }
 
     if total_items == 0 {
         // Render empty list with message
         let empty_list = List::new(Vec::<ListItem>::new())
-            .block(Block::default().borders(Borders::ALL).title(title))
-            .highlight_style(
-                Style::default()
-                    .bg(Color::Blue)
-                    .add_modifier(Modifier::BOLD),
-            );
+            .block(block)
+            .highlight_style(theme.selection());
         f.render_stateful_widget(empty_list, area, &mut app.list_state);
         return;
     }
@@ -200,24 +259,27 @@ fn render_package_list(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect
                 let content = if Some(i) == app.list_state.selected() {
                     apply_horizontal_scroll(&display_name, available_width, app)
                 } else {
-                    display_name
+                    display_name.clone()
                 };
 
-                let style = get_package_style(package);
-                ListItem::new(Line::from(Span::styled(content, style)))
+                // Matches can only be highlighted while the name is not scrolled
+                let positions = if content == display_name {
+                    matched_name_positions(app, package)
+                } else {
+                    Vec::new()
+                };
+                let style = list_package_style(app, package);
+                ListItem::new(Line::from(highlight_matches(&content, &positions, style)))
             })
             .collect();
 
         let items_list = List::new(list_items)
-            .block(Block::default().borders(Borders::ALL).title(title))
-            .highlight_style(
-                Style::default()
-                    .bg(Color::Blue)
-                    .add_modifier(Modifier::BOLD),
-            )
+            .block(block)
+            .highlight_style(theme.selection())
             .highlight_symbol(">> ");
 
         f.render_stateful_widget(items_list, area, &mut app.list_state);
+        app.mouse_targets.list = Some(area.inner(Margin::new(1, 1)));
     } else {
         // Multi-column layout using table
         let column_width = available_width / visible_columns;
@@ -237,7 +299,7 @@ fn render_package_list(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect
         let selected_idx = app.list_state.selected();
 
         for row_idx in 0..rows_per_column {
-            let mut cells: Vec<Span> = Vec::new();
+            let mut cells: Vec<Line> = Vec::new();
 
             for visible_col_idx in 0..visible_columns {
                 // Calculate the actual column index considering scroll offset
@@ -248,58 +310,142 @@ fn render_package_list(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect
                     let package = &items[item_idx];
                     let display_name = package.get_display_name();
 
+                    let mut positions = matched_name_positions(app, package);
+
                     // Truncate name to fit column width (Unicode-safe)
                     let truncated_name = if display_name.chars().count() > column_width - 2 {
                         let chars: Vec<char> = display_name.chars().collect();
                         let truncate_at = column_width.saturating_sub(3);
+                        positions.retain(|&position| position < truncate_at);
                         format!("{}…", chars[..truncate_at].iter().collect::<String>())
                     } else {
                         display_name
                     };
 
-                    let style = get_package_style(package);
+                    let style = list_package_style(app, package);
 
                     // Check if this item is selected
                     let is_selected = selected_idx == Some(item_idx);
                     let final_style = if is_selected {
-                        style.bg(Color::Blue).add_modifier(Modifier::BOLD)
+                        style.patch(theme.selection())
                     } else {
                         style
                     };
 
                     let prefix = if is_selected { ">> " } else { "   " };
-                    cells.push(Span::styled(
-                        format!("{}{}", prefix, truncated_name),
+                    let positions: Vec<usize> = positions
+                        .iter()
+                        .map(|position| position + prefix.len())
+                        .collect();
+                    cells.push(Line::from(highlight_matches(
+                        &format!("{}{}", prefix, truncated_name),
+                        &positions,
                         final_style,
-                    ));
+                    )));
                 } else {
                     // Empty cell for alignment
-                    cells.push(Span::raw(""));
+                    cells.push(Line::raw(""));
                 }
             }
 
             table_rows.push(Row::new(cells));
         }
 
+        // Table columns are laid out the same way, so they can be clicked
+        app.mouse_targets.columns = Layout::horizontal(constraints.clone())
+            .spacing(1)
+            .split(area.inner(Margin::new(1, 1)))
+            .to_vec();
+
         let table = Table::new(table_rows, constraints)
-            .block(Block::default().borders(Borders::ALL).title(title))
+            .block(block)
             .column_spacing(1);
 
         f.render_widget(table, area);
     }
 }
 
+/// Describes how old the displayed package list is, and whether it is being refreshed
+fn refresh_indicator(app: &App) -> String {
+    let age = helpers::format_time_ago(helpers::unix_now().saturating_sub(app.refreshed_at));
+    match (app.stale_since.is_some(), app.is_reloading()) {
+        (true, true) => format!("[stale: cached {}, refreshing…]", age),
+        (true, false) => format!("[stale: cached {}]", age),
+        (false, true) => format!("[refreshed {}, refreshing…]", age),
+        (false, false) => format!("[refreshed {}]", age),
+    }
+}
+
 /// Gets the appropriate style for a package based on its status
-fn get_package_style(package: &crate::entities::package_info::PackageInfo) -> Style {
-    if package.outdated || package.has_update_available() {
-        // Use a more visible reddish color for packages with updates available
-        Style::default().fg(Color::Rgb(220, 80, 80)) // Soft reddish color
+fn get_package_style(package: &crate::entities::package_info::PackageInfo, theme: &Theme) -> Style {
+    // Use a more visible style for packages with updates available.
+    // All packages are installed (since they come from brew --installed).
+    theme.package(package.outdated || package.has_update_available())
+}
+
+/// Gets the style of a package in the list, underlining packages that became outdated at
+/// the latest refresh
+fn list_package_style(app: &App, package: &crate::entities::package_info::PackageInfo) -> Style {
+    let style = get_package_style(package, &app.theme);
+    if app.is_newly_outdated(package) {
+        style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
     } else {
-        // All packages are installed (since they come from brew --installed)
-        Style::default().fg(Color::Green)
+        style
     }
 }
 
+/// Gets the indices of the characters of a package's display name that match the search query
+fn matched_name_positions(
+    app: &App,
+    package: &crate::entities::package_info::PackageInfo,
+) -> Vec<usize> {
+    let Some(found) = search_match(app, package) else {
+        return Vec::new();
+    };
+    // The display name starts with the package type icon
+    let icon_width = package.get_display_name().chars().count() - package.name.chars().count();
+    found
+        .name_positions
+        .iter()
+        .map(|position| position + icon_width)
+        .collect()
+}
+
+/// Matches a package against the search query while searching
+fn search_match(
+    app: &App,
+    package: &crate::entities::package_info::PackageInfo,
+) -> Option<PackageMatch> {
+    if app.is_searching {
+        fuzzy::match_package(package, &app.query.text)
+    } else {
+        None
+    }
+}
+
+/// Splits text into spans, emphasising the characters at the given indices
+fn highlight_matches(text: &str, positions: &[usize], style: Style) -> Vec<Span<'static>> {
+    let matched_style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+
+    for (index, c) in text.chars().enumerate() {
+        let matched = positions.contains(&index);
+        if matched != run_matched && !run.is_empty() {
+            let style = if run_matched { matched_style } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        let style = if run_matched { matched_style } else { style };
+        spans.push(Span::styled(run, style));
+    }
+    spans
+}
+
 /// Applies horizontal scrolling to a package name
 fn apply_horizontal_scroll(name: &str, available_width: usize, app: &App) -> String {
     let chars: Vec<char> = name.chars().collect();
@@ -326,14 +472,36 @@ fn apply_horizontal_scroll(name: &str, available_width: usize, app: &App) -> Str
     }
 }
 
-/// Renders the package details on the right panel
-fn render_package_details(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+/// Renders the package details on the right panel.
+/// Returns the area of the homepage link if it is visible.
+fn render_package_details(
+    f: &mut Frame,
+    app: &App,
+    area: ratatui::layout::Rect,
+) -> Option<ratatui::layout::Rect> {
     let package_details = app.get_selected_package_details();
-    let details = match package_details.as_ref() {
-        Some(package) => create_package_details_text(package),
-        None => Text::from("No package selected"),
+    let (details, homepage_line) = match package_details.as_ref() {
+        Some(package) => {
+            let found = search_match(app, package);
+            create_package_details_text(package, found.as_ref(), &app.theme, &app.keymap)
+        }
+        None => (Text::from("No package selected"), None),
     };
 
+    // Find the rows the homepage line wraps onto
+    let inner = area.inner(Margin::new(1, 1));
+    let homepage_area = homepage_line.and_then(|line| {
+        let wrapped_rows = |lines: &[Line]| {
+            Paragraph::new(lines.to_vec())
+                .wrap(Wrap { trim: false })
+                .line_count(inner.width) as u16
+        };
+        let y = inner.y + wrapped_rows(&details.lines[..line]);
+        let height = wrapped_rows(&details.lines[line..=line]);
+        let homepage_area = ratatui::layout::Rect::new(inner.x, y, inner.width, height);
+        Some(homepage_area.intersection(inner)).filter(|area| !area.is_empty())
+    });
+
     let details_paragraph = Paragraph::new(details)
         .block(
             Block::default()
@@ -345,34 +513,83 @@ fn render_package_details(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
     f.render_widget(details_paragraph, area);
 
     // Render help text at the bottom
-    render_help_text(f, area);
+    render_help_text(f, app, area);
+
+    homepage_area
 }
 
-/// Creates the detailed text for a package
-fn create_package_details_text(package: &crate::entities::package_info::PackageInfo) -> Text<'_> {
+/// Creates the detailed text for a package, along with the index of the homepage line if it
+/// has a homepage. The characters matching the search query are highlighted.
+fn create_package_details_text<'a>(
+    package: &'a crate::entities::package_info::PackageInfo,
+    found: Option<&PackageMatch>,
+    theme: &Theme,
+    keymap: &Keymap,
+) -> (Text<'a>, Option<usize>) {
     let installed_status = package.installation_status();
-    let status_colour = if package.outdated || package.has_update_available() {
-        // Use the same reddish color for packages with updates available
-        Color::Rgb(220, 80, 80) // Soft reddish color
-    } else {
-        // All packages are installed (since they come from brew --installed)
-        Color::Green
+    // Use the same style as the package list
+    let status_style = get_package_style(package, theme);
+    let (name_positions, description_positions, note_positions) = match found {
+        Some(found) => (
+            &found.name_positions[..],
+            &found.description_positions[..],
+            &found.note_positions[..],
+        ),
+        None => (&[][..], &[][..], &[][..]),
     };
 
+    let mut name_line = vec![Span::styled(
+        "Name: ",
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    name_line.extend(highlight_matches(
+        &package.name,
+        name_positions,
+        Style::default(),
+    ));
+    let mut description_line = vec![Span::styled(
+        "Description: ",
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    description_line.extend(highlight_matches(
+        &package.description,
+        description_positions,
+        Style::default(),
+    ));
+
     let mut lines = vec![
-        Line::from(vec![
-            Span::styled("Name: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(&package.name),
-        ]),
+        Line::from(name_line),
         Line::from(""),
-        Line::from(vec![
+        Line::from(description_line),
+        Line::from(""),
+    ];
+
+    if let Some(note) = &package.note {
+        let mut note_line = vec![Span::styled(
+            "Note: ",
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        note_line.extend(highlight_matches(
+            note,
+            note_positions,
+            Style::default().fg(theme.accent),
+        ));
+        lines.push(Line::from(note_line));
+        lines.push(Line::from(""));
+    }
+
+    if !package.collections.is_empty() {
+        lines.push(Line::from(vec![
             Span::styled(
-                "Description: ",
+                "Collections: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw(&package.description),
-        ]),
-        Line::from(""),
+            Span::raw(package.collections.join(", ")),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    lines.extend([
         Line::from(vec![
             Span::styled("Tap: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(package.tap.as_deref().unwrap_or("unknown")),
@@ -383,9 +600,22 @@ fn create_package_details_text(package: &crate::entities::package_info::PackageI
             Span::raw(package.caveats.as_deref().unwrap_or("none")),
         ]),
         Line::from(""),
+    ]);
+
+    let homepage_line = package.homepage.as_ref().map(|_| lines.len());
+    let homepage = match &package.homepage {
+        Some(homepage) => Span::styled(
+            homepage,
+            Style::default()
+                .fg(theme.link)
+                .add_modifier(Modifier::UNDERLINED),
+        ),
+        None => Span::styled("No homepage available", Style::default().fg(theme.muted)),
+    };
+    lines.extend([
         Line::from(vec![
             Span::styled("Homepage: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::styled(&package.homepage, Style::default().fg(Color::Blue)),
+            homepage,
         ]),
         Line::from(""),
         Line::from(vec![
@@ -393,7 +623,7 @@ fn create_package_details_text(package: &crate::entities::package_info::PackageI
                 "Installed Version: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::styled(installed_status, Style::default().fg(status_colour)),
+            Span::styled(installed_status, status_style),
         ]),
         Line::from(""),
         Line::from(vec![
@@ -404,33 +634,114 @@ fn create_package_details_text(package: &crate::entities::package_info::PackageI
             Span::raw(&package.current_version),
         ]),
         Line::from(""),
-    ];
+    ]);
+
+    if package.deprecated {
+        lines.push(Line::from(vec![
+            Span::styled("Status: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(
+                "deprecated or disabled in its tap",
+                Style::default().fg(theme.danger),
+            ),
+        ]));
+        lines.push(Line::from(""));
+    }
 
     // Add installation time if available
     if let Some(time_ago) = package.installed_ago() {
         lines.push(Line::from(vec![
             Span::styled("Installed: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::styled(time_ago, Style::default().fg(Color::Cyan)),
+            Span::styled(time_ago, Style::default().fg(theme.accent)),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    if package.pinned {
+        lines.push(Line::from(vec![
+            Span::styled("Pinned: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("yes, held back from upgrades"),
         ]));
         lines.push(Line::from(""));
     }
 
+    if let Some(size) = package.installed_size {
+        lines.push(Line::from(vec![
+            Span::styled("Size: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(helpers::format_size(size)),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    // Add install receipt details if the keg has one
+    if let Some(receipt) = package.receipt() {
+        lines.extend(create_receipt_lines(receipt));
+    }
+
     lines.push(Line::from(""));
 
     // Add the action hints as separate lines
-    lines.extend(create_action_hints(package));
+    lines.extend(create_action_hints(package, theme, keymap));
 
-    Text::from(lines)
+    (Text::from(lines), homepage_line)
+}
+
+/// Creates the lines describing how the installed keg was built, taken from its install receipt
+fn create_receipt_lines(
+    receipt: &crate::entities::install_receipt::InstallReceipt,
+) -> Vec<Line<'static>> {
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+    let options = if receipt.used_options.is_empty() {
+        "none".to_string()
+    } else {
+        receipt.used_options.join(" ")
+    };
+    let dependencies = receipt.runtime_dependency_names();
+    let dependencies = if dependencies.is_empty() {
+        "none".to_string()
+    } else {
+        dependencies.join(", ")
+    };
+
+    let fields = [
+        (
+            "Poured From Bottle: ",
+            yes_no(receipt.poured_from_bottle).to_string(),
+        ),
+        (
+            "Built As Bottle: ",
+            yes_no(receipt.built_as_bottle).to_string(),
+        ),
+        (
+            "Build Spec: ",
+            receipt.spec().unwrap_or("unknown").to_string(),
+        ),
+        ("Used Options: ", options),
+        ("Runtime Dependencies: ", dependencies),
+    ];
+
+    let mut lines = Vec::new();
+    for (label, value) in fields {
+        lines.push(Line::from(vec![
+            Span::styled(label, Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(value),
+        ]));
+        lines.push(Line::from(""));
+    }
+    lines
 }
 
 /// Creates action hints based on package state
-fn create_action_hints(package: &crate::entities::package_info::PackageInfo) -> Vec<Line<'_>> {
+fn create_action_hints<'a>(
+    package: &'a crate::entities::package_info::PackageInfo,
+    theme: &Theme,
+    keymap: &Keymap,
+) -> Vec<Line<'a>> {
     let mut lines = vec![
         Line::from(vec![Span::styled(
             "⚡ Actions:",
             Style::default()
                 .add_modifier(Modifier::BOLD)
-                .fg(Color::Cyan),
+                .fg(theme.accent),
         )]),
         Line::from(""),
     ];
@@ -441,9 +752,17 @@ fn create_action_hints(package: &crate::entities::package_info::PackageInfo) ->
         Span::raw("    ◦ "),
         Span::styled(
             "uninstall",
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(theme.danger)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                " (press '{}' to remove)",
+                keymap.first_key(KeyMode::Normal, Action::Uninstall)
+            ),
+            Style::default().fg(theme.muted),
         ),
-        Span::styled(" (press 'x' to remove)", Style::default().fg(Color::Gray)),
     ]));
 
     // Only add update action if update is available
@@ -453,30 +772,95 @@ fn create_action_hints(package: &crate::entities::package_info::PackageInfo) ->
             Span::styled(
                 "update",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.warning)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" (press 'u' to update)", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!(
+                    " (press '{}' to update)",
+                    keymap.first_key(KeyMode::Normal, Action::Update)
+                ),
+                Style::default().fg(theme.muted),
+            ),
         ]));
     }
 
+    lines.push(Line::from(vec![
+        Span::raw("    ◦ "),
+        Span::styled(
+            "note",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                " (press '{}' to {} a note)",
+                keymap.first_key(KeyMode::Normal, Action::EditNote),
+                if package.note.is_some() {
+                    "edit"
+                } else {
+                    "add"
+                }
+            ),
+            Style::default().fg(theme.muted),
+        ),
+    ]));
+
     lines
 }
 
+/// Pairs of opposite movements, in the order the navigation hints list them
+const NAVIGATION: [(Action, Action); 4] = [
+    (Action::MoveUp, Action::MoveDown),
+    (Action::MoveLeft, Action::MoveRight),
+    (Action::PageUp, Action::PageDown),
+    (Action::First, Action::Last),
+];
+
+/// Gets the first keys of the movement pairs the mode supports, like "↑/↓ PgUp/PgDn"
+fn navigation_keys(keymap: &Keymap, mode: KeyMode, pairs: &[(Action, Action)]) -> String {
+    pairs
+        .iter()
+        .filter(|(back, forward)| mode.supports(*back) && mode.supports(*forward))
+        .map(|&(back, forward)| {
+            format!(
+                "{}/{}",
+                keymap.first_key(mode, back),
+                keymap.first_key(mode, forward)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Renders help text at the bottom of the details panel
-fn render_help_text(f: &mut Frame, area: ratatui::layout::Rect) {
+fn render_help_text(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let key = |action| app.keymap.first_key(KeyMode::Normal, action);
     let help_text = vec![
-        Span::raw("Navigate: ↑/↓ ←/→ | Search: / | Actions: u/x | "),
+        Span::raw(format!(
+            "Navigate: {} | Search: {} | Commands: {} | Actions: {}/{} | ",
+            navigation_keys(&app.keymap, KeyMode::Normal, &NAVIGATION[..2]),
+            key(Action::Search),
+            key(Action::CommandPalette),
+            key(Action::Update),
+            key(Action::Uninstall)
+        )),
         Span::styled(
-            "g",
+            key(Action::OpenIssues),
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::raw(": Submit Issue | Quit: q"),
+        Span::raw(format!(
+            ": Submit Issue | Help: {} | Quit: {}",
+            key(Action::Help),
+            key(Action::Quit)
+        )),
     ];
     let help_paragraph =
-        Paragraph::new(Line::from(help_text)).style(Style::default().fg(Color::Gray));
+        Paragraph::new(Line::from(help_text)).style(Style::default().fg(theme.muted));
 
     let help_area = area.inner(Margin {
         horizontal: 1,
@@ -493,45 +877,294 @@ fn render_help_text(f: &mut Frame, area: ratatui::layout::Rect) {
     f.render_widget(help_paragraph, help_rect);
 }
 
+/// Renders the install timeline, listing installs and upgrades by date
+fn render_timeline(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let list_items: Vec<ListItem> = app
+        .timeline
+        .iter()
+        .map(|entry| {
+            let (event_text, event_colour) = match entry.event {
+                TimelineEvent::Installed => ("installed", theme.success),
+                TimelineEvent::Upgraded => ("upgraded ", theme.warning),
+            };
+            let source = if entry.poured_from_bottle {
+                "bottle"
+            } else {
+                "source"
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} UTC  ", helpers::format_date(entry.time)),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::styled(event_text, Style::default().fg(event_colour)),
+                Span::raw("  "),
+                Span::styled(
+                    entry.package_name.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(" {} ", entry.version)),
+                Span::styled(format!("({})", source), Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+
+    let title = if app.timeline.is_empty() {
+        "Install Timeline - No install receipts found".to_string()
+    } else {
+        format!("Install Timeline ({} entries)", app.timeline.len())
+    };
+
+    let timeline_list = List::new(list_items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(app.theme.selection())
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(timeline_list, area, &mut app.timeline_state);
+    app.mouse_targets.list = Some(area.inner(Margin::new(1, 1)));
+}
+
+/// Renders the operation history, listing logged operations by date next to the details
+/// and output of the selected one
+fn render_history(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(app.config.layout.list_width_percent),
+            Constraint::Percentage(100 - app.config.layout.list_width_percent),
+        ])
+        .split(area);
+
+    let theme = app.theme.clone();
+    let operations = app.shown_operations();
+    let list_items: Vec<ListItem> = operations
+        .iter()
+        .map(|record| {
+            let (outcome, outcome_colour) = if record.succeeded() {
+                ("✓", theme.success)
+            } else {
+                ("✗", theme.danger)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} UTC  ", helpers::format_date(record.time)),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::styled(outcome, Style::default().fg(outcome_colour)),
+                Span::raw(format!(" {:<11}  ", record.kind.label())),
+                Span::styled(
+                    record.package.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(" {}", version_change(record)),
+                    Style::default().fg(theme.muted),
+                ),
+            ]))
+        })
+        .collect();
+
+    let title = match &app.operations_filter {
+        _ if app.operations.is_empty() => "History - No operations logged yet".to_string(),
+        Some(package) => format!("History of {} ({} entries)", package, operations.len()),
+        None => format!("History ({} entries)", operations.len()),
+    };
+    let selected = app.selected_operation().cloned();
+
+    let history_list = List::new(list_items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(theme.selection())
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(history_list, chunks[0], &mut app.operations_state);
+    app.mouse_targets.list = Some(chunks[0].inner(Margin::new(1, 1)));
+
+    // Scroll long output to its end, where brew explains failures
+    let details = match &selected {
+        Some(record) => operation_details(record, &theme),
+        None => Text::from("No operation selected"),
+    };
+    let inner = chunks[1].inner(Margin::new(1, 1));
+    let paragraph = Paragraph::new(details).wrap(Wrap { trim: false });
+    let overflow = (paragraph.line_count(inner.width) as u16).saturating_sub(inner.height);
+    f.render_widget(
+        paragraph
+            .scroll((overflow, 0))
+            .block(Block::default().borders(Borders::ALL).title("Operation")),
+        chunks[1],
+    );
+}
+
+/// Describes the versions an operation went from and to, such as "1.7 → 1.7.1"
+fn version_change(record: &OperationRecord) -> String {
+    match (&record.from_version, &record.to_version) {
+        (Some(from), Some(to)) => format!("{} → {}", from, to),
+        (Some(version), None) | (None, Some(version)) => version.clone(),
+        (None, None) => String::new(),
+    }
+}
+
+/// Creates the details of a logged operation, followed by everything brew printed
+fn operation_details<'a>(record: &'a OperationRecord, theme: &Theme) -> Text<'a> {
+    let label = |text| Span::styled(text, Style::default().add_modifier(Modifier::BOLD));
+    let exit_status = match record.exit_code {
+        Some(0) => Span::styled("success", Style::default().fg(theme.success)),
+        Some(code) => Span::styled(
+            format!("failed with exit code {}", code),
+            Style::default().fg(theme.danger),
+        ),
+        None => Span::styled("brew did not finish", Style::default().fg(theme.danger)),
+    };
+
+    let mut lines = vec![
+        Line::from(vec![label("Package: "), Span::raw(&record.package)]),
+        Line::from(vec![label("Operation: "), Span::raw(record.kind.label())]),
+        Line::from(vec![
+            label("Date: "),
+            Span::raw(format!("{} UTC", helpers::format_date(record.time))),
+        ]),
+        Line::from(vec![
+            label("Version: "),
+            match version_change(record) {
+                change if change.is_empty() => {
+                    Span::styled("unknown", Style::default().fg(theme.muted))
+                }
+                change => Span::raw(change),
+            },
+        ]),
+        Line::from(vec![label("Status: "), exit_status]),
+    ];
+    if let Some(removed) = record.undoable_removal()
+        && !removed.options.is_empty()
+    {
+        lines.push(Line::from(vec![
+            label("Options: "),
+            Span::raw(removed.options.join(" ")),
+        ]));
+    }
+    lines.extend([Line::from(""), Line::from(label("Output:"))]);
+    if record.output.trim().is_empty() {
+        lines.push(Line::styled("(none)", Style::default().fg(theme.muted)));
+    } else {
+        lines.extend(record.output.lines().map(Line::raw));
+    }
+    Text::from(lines)
+}
+
 /// Renders the status bar at the bottom of the screen
 fn render_status_bar(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
-    let status_text = if let Some(update_status) = app.get_update_status() {
+    let status_text = if let Some(command_line) = &app.command_line {
+        // The command line takes over the status bar, followed by the completions of the word
+        // being typed or the latest message
+        let (input, cursor_offset) = visible_input(command_line, area.width.saturating_sub(1));
+        let hint = if app.completions.is_empty() {
+            app.get_current_status().unwrap_or_default()
+        } else {
+            app.completions.join("  ")
+        };
+        f.set_cursor_position(Position::new(area.x + 1 + cursor_offset, area.y));
+        Text::from(Line::from(vec![
+            Span::raw(format!(":{}", input)),
+            Span::styled(format!("   {}", hint), Style::default().fg(app.theme.muted)),
+        ]))
+    } else if let Some(update_status) = app.get_update_status() {
         // Prioritise update status when an update is in progress
         Text::from(update_status)
     } else if let Some(message) = app.get_current_status() {
         Text::from(message)
+    } else if !app.pending_keys.is_empty() {
+        Text::from(format!("Keys: {} …", format_sequence(&app.pending_keys)))
+    } else if app.screen == Screen::Timeline {
+        let key = |action| app.keymap.keys_label(KeyMode::Timeline, action);
+        Text::from(format!(
+            "Navigate: {} | Show Package: {} | Back: {} | Commands: {} | Help: {} | Quit: {}",
+            navigation_keys(&app.keymap, KeyMode::Timeline, &NAVIGATION),
+            key(Action::OpenEntry),
+            key(Action::Timeline),
+            key(Action::CommandPalette),
+            key(Action::Help),
+            key(Action::Quit)
+        ))
+    } else if app.screen == Screen::History {
+        let key = |action| app.keymap.keys_label(KeyMode::History, action);
+        Text::from(format!(
+            "Navigate: {} | This Package Only: {} | Undo Uninstall: {} | Show Package: {} | Back: {} | Commands: {} | Help: {} | Quit: {}",
+            navigation_keys(&app.keymap, KeyMode::History, &NAVIGATION),
+            key(Action::FilterPackage),
+            key(Action::Undo),
+            key(Action::OpenEntry),
+            key(Action::History),
+            key(Action::CommandPalette),
+            key(Action::Help),
+            key(Action::Quit)
+        ))
+    } else if app.is_searching {
+        let key = |action| app.keymap.keys_label(KeyMode::Search, action);
+        Text::from(format!(
+            "Results: {} {} | History: {} {} | Save Search: {} | Commands: {} | Done: {} | Cancel: {}",
+            key(Action::MoveUp),
+            key(Action::MoveDown),
+            key(Action::HistoryPrevious),
+            key(Action::HistoryNext),
+            key(Action::SaveSearch),
+            key(Action::CommandPalette),
+            key(Action::SubmitSearch),
+            key(Action::EndSearch)
+        ))
     } else {
-        // Default help text with highlighted 'g' key
+        // Default help text with highlighted issue key
+        let key = |action| app.keymap.first_key(KeyMode::Normal, action);
         Text::from(Line::from(vec![
-            Span::raw("Navigate: ↑/↓ ←/→ PgUp/PgDn Home/End | Search: / | Actions: u/x | "),
+            Span::raw(format!(
+                "Navigate: {} | Search: {} | Actions: {}/{} | Timeline: {} | ",
+                navigation_keys(&app.keymap, KeyMode::Normal, &NAVIGATION),
+                key(Action::Search),
+                key(Action::Update),
+                key(Action::Uninstall),
+                key(Action::Timeline)
+            )),
             Span::styled(
-                "g",
+                key(Action::OpenIssues),
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(app.theme.accent)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(": Submit Issue | Quit: q"),
+            Span::raw(format!(
+                ": Submit Issue | Help: {} | Quit: {}",
+                key(Action::Help),
+                key(Action::Quit)
+            )),
         ]))
     };
 
     let status_paragraph = Paragraph::new(status_text)
-        .style(Style::default().bg(Color::Blue).fg(Color::White))
+        .style(app.theme.status_bar())
         .wrap(Wrap { trim: true });
 
     f.render_widget(status_paragraph, area);
 }
 
-/// Renders modal windows
-fn render_modal(f: &mut Frame, app: &App) {
-    match app.modal_state {
+/// Renders modal windows, returning the areas of their buttons and list rows
+fn render_modal(f: &mut Frame, app: &App) -> MouseTargets {
+    let mut targets = MouseTargets::default();
+    match &app.modal_state {
         ModalState::UpdateProgress => render_update_modal(f, app),
-        ModalState::UninstallConfirmation => render_uninstall_confirmation_modal(f, app),
-        ModalState::None => {}
+        ModalState::UninstallConfirmation => {
+            targets.buttons = render_uninstall_confirmation_modal(f, app)
+        }
+        ModalState::Prompt(prompt) => targets.buttons = render_prompt_modal(f, app, prompt),
+        ModalState::Picker(picker) => {
+            (targets.buttons, targets.rows) = render_picker_modal(f, app, picker)
+        }
+        ModalState::Help | ModalState::None => {}
     }
+    targets
 }
 
 /// Renders the update progress modal
 fn render_update_modal(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let area = f.area();
 
     // Create a centered modal area
@@ -627,30 +1260,39 @@ fn render_update_modal(f: &mut Frame, app: &App) {
 
     let content = vec![
         Line::from(""),
-        Line::from(Span::styled(details, Style::default().fg(Color::Cyan))),
+        Line::from(Span::styled(details, Style::default().fg(theme.accent))),
         Line::from(""),
         Line::from(progress_text),
         Line::from(""),
-        Line::from(""),
+        Line::from(match app.operation_queue.len() {
+            0 => String::new(),
+            queued => format!("{} more queued", queued),
+        }),
         Line::from(Span::styled(
             if app.is_uninstalling {
                 "Uninstall in progress... Please wait for completion."
+            } else if app.reinstalling.is_some() {
+                "Reinstall in progress... Please wait for completion."
             } else {
                 "Update in progress... Please wait for completion."
             },
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.warning)
                 .add_modifier(Modifier::ITALIC),
         )),
     ];
 
     // Create the modal block
+    let modal_title = match modal_title {
+        "Updating" if app.reinstalling.is_some() => "Reinstalling",
+        title => title,
+    };
     let title = format!("{} {}", modal_title, package_name);
     let modal_block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Blue))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(theme.modal_border))
+        .style(Style::default().bg(theme.modal_background));
 
     // Split modal area for content and progress bar
     let modal_layout = Layout::default()
@@ -673,19 +1315,23 @@ fn render_update_modal(f: &mut Frame, app: &App) {
     // Render progress bar
     let progress_gauge = Gauge::default()
         .block(Block::default().borders(Borders::ALL).title("Progress"))
-        .gauge_style(Style::default().fg(Color::Green))
+        .gauge_style(Style::default().fg(theme.success))
         .percent(progress)
         .label(format!("{}%", progress));
     f.render_widget(progress_gauge, modal_layout[1]);
 }
 
-/// Renders the uninstall confirmation modal
-fn render_uninstall_confirmation_modal(f: &mut Frame, app: &App) {
+/// Renders the uninstall confirmation modal, returning the areas of its buttons
+fn render_uninstall_confirmation_modal(
+    f: &mut Frame,
+    app: &App,
+) -> Vec<(ratatui::layout::Rect, Action)> {
+    let theme = &app.theme;
     let area = f.area();
 
     // Create a centered modal area
     let modal_width = 50;
-    let modal_height = 8;
+    let modal_height = 10;
     let x = (area.width.saturating_sub(modal_width)) / 2;
     let y = (area.height.saturating_sub(modal_height)) / 2;
 
@@ -699,52 +1345,64 @@ fn render_uninstall_confirmation_modal(f: &mut Frame, app: &App) {
     // Clear the area behind the modal
     f.render_widget(Clear, modal_area);
 
-    // Get package name
-    let package_name = app
-        .pending_uninstall_package
-        .as_deref()
-        .unwrap_or("Unknown Package");
+    // Name the package, or count the packages of a collection
+    let question = match app.pending_uninstall.as_slice() {
+        [package_name] => format!("Are you sure you want to uninstall '{}'?", package_name),
+        packages if packages.len() <= 3 => format!(
+            "Are you sure you want to uninstall {}?",
+            packages.join(", ")
+        ),
+        packages => format!(
+            "Are you sure you want to uninstall {} and {} more packages?",
+            packages[..2].join(", "),
+            packages.len() - 2
+        ),
+    };
 
     // Create modal content
     let content = vec![
         Line::from(""),
         Line::from(Span::styled(
-            format!("Are you sure you want to uninstall '{}'?", package_name),
+            question,
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.warning)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(Span::styled(
             "This action cannot be undone.",
             Style::default()
-                .fg(Color::Gray)
+                .fg(theme.muted)
                 .add_modifier(Modifier::ITALIC),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("Press ", Style::default().fg(Color::Gray)),
+            Span::styled("Press ", Style::default().fg(theme.muted)),
             Span::styled(
-                "Y",
+                app.keymap.first_key(KeyMode::Confirm, Action::Confirm),
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme.success)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" to confirm, ", Style::default().fg(Color::Gray)),
+            Span::styled(" to confirm, ", Style::default().fg(theme.muted)),
             Span::styled(
-                "N",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                app.keymap.first_key(KeyMode::Confirm, Action::Cancel),
+                Style::default()
+                    .fg(theme.danger)
+                    .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" to cancel", Style::default().fg(Color::Gray)),
+            Span::styled(" to cancel", Style::default().fg(theme.muted)),
         ]),
     ];
 
+    let content_height = content.len() as u16 + 1;
+
     // Create the modal block
     let modal_block = Block::default()
         .title("⚠️  Confirm Uninstall")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(theme.danger))
+        .style(Style::default().bg(theme.modal_background));
 
     // Render modal background
     f.render_widget(modal_block.clone(), modal_area);
@@ -755,4 +1413,470 @@ fn render_uninstall_confirmation_modal(f: &mut Frame, app: &App) {
         .alignment(Alignment::Center)
         .wrap(Wrap { trim: true });
     f.render_widget(content_paragraph, modal_area);
+
+    // Render the buttons on the row below the content, centred
+    let buttons = [
+        (" Uninstall ", Action::Confirm, theme.danger),
+        (" Cancel ", Action::Cancel, theme.muted),
+    ];
+    let gap = 4;
+    let total_width: u16 = buttons
+        .iter()
+        .map(|(label, _, _)| label.len() as u16)
+        .sum::<u16>()
+        + gap;
+    let mut button_x = modal_area.x + modal_area.width.saturating_sub(total_width) / 2;
+    let button_y = modal_area.y + 1 + content_height;
+
+    buttons
+        .into_iter()
+        .map(|(label, action, colour)| {
+            let button_area = ratatui::layout::Rect::new(button_x, button_y, label.len() as u16, 1)
+                .intersection(modal_area);
+            f.render_widget(
+                Paragraph::new(label).style(theme.button(colour)),
+                button_area,
+            );
+            button_x += label.len() as u16 + gap;
+            (button_area, action)
+        })
+        .collect()
+}
+
+/// Renders the help overlay, listing the active bindings of the mode it was opened from.
+/// Returns how many bindings fit, so the rest can be scrolled to.
+fn render_help_modal(f: &mut Frame, app: &App) -> usize {
+    let theme = &app.theme;
+    let area = f.area();
+    let bindings = app.keymap.bindings(app.help_mode);
+
+    let keys_width = bindings
+        .iter()
+        .map(|binding| {
+            app.keymap
+                .keys_label(app.help_mode, binding.action)
+                .chars()
+                .count()
+        })
+        .max()
+        .unwrap_or(0);
+
+    // The borders, a blank line and the hint take four rows, the bindings get the rest
+    let modal_width = 60.min(area.width);
+    let modal_height = (bindings.len() as u16 + 4).min(area.height);
+    let rows = (modal_height as usize).saturating_sub(4).max(1);
+    let first = app.help_scroll.min(bindings.len().saturating_sub(rows));
+
+    let mut content: Vec<Line> = bindings
+        .iter()
+        .skip(first)
+        .take(rows)
+        .map(|binding| {
+            let keys = app.keymap.keys_label(app.help_mode, binding.action);
+            let padding = " ".repeat(keys_width - keys.chars().count() + 1);
+            Line::from(vec![
+                Span::raw(padding),
+                Span::styled(
+                    keys,
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("  "),
+                Span::raw(binding.action.description()),
+            ])
+        })
+        .collect();
+    let scrolls = bindings.len() > rows;
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        if scrolls {
+            // Scrolling uses the movement keys of the package list
+            format!(
+                " Scroll with {}, press any other key or click to close",
+                navigation_keys(&app.keymap, KeyMode::Normal, &NAVIGATION[..1])
+            )
+        } else {
+            " Press any key or click to close".to_string()
+        },
+        Style::default()
+            .fg(theme.muted)
+            .add_modifier(Modifier::ITALIC),
+    )));
+
+    // Create a centered modal area that fits the bindings
+    let modal_area = ratatui::layout::Rect {
+        x: (area.width.saturating_sub(modal_width)) / 2,
+        y: (area.height.saturating_sub(modal_height)) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+
+    // Clear the area behind the modal
+    f.render_widget(Clear, modal_area);
+
+    let mut modal_block = Block::default()
+        .title(format!("Key Bindings - {}", app.help_mode.title()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.modal_background));
+    if scrolls {
+        modal_block = modal_block.title_bottom(
+            Line::from(format!(
+                " {}-{} of {} ",
+                first + 1,
+                (first + rows).min(bindings.len()),
+                bindings.len()
+            ))
+            .right_aligned(),
+        );
+    }
+
+    let content_paragraph = Paragraph::new(content).block(modal_block);
+    f.render_widget(content_paragraph, modal_area);
+    rows
+}
+
+/// Renders a modal asking for one line of text
+fn render_prompt_modal(
+    f: &mut Frame,
+    app: &App,
+    prompt: &Prompt,
+) -> Vec<(ratatui::layout::Rect, Action)> {
+    let theme = &app.theme;
+    let area = f.area();
+
+    // Create a centered modal area
+    let modal_width = 60.min(area.width);
+    let modal_height = 5.min(area.height);
+    let modal_area = ratatui::layout::Rect {
+        x: (area.width.saturating_sub(modal_width)) / 2,
+        y: (area.height.saturating_sub(modal_height)) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+
+    // Clear the area behind the modal
+    f.render_widget(Clear, modal_area);
+
+    let modal_block = Block::default()
+        .title(format!(" {} ", prompt.title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.modal_border))
+        .style(Style::default().bg(theme.modal_background));
+    let inner = modal_block.inner(modal_area);
+
+    let (input, cursor_offset) = visible_input(&prompt.input, inner.width);
+    let (hint, buttons) = modal_hint(
+        app,
+        KeyMode::Prompt,
+        &[(Action::Confirm, "confirm"), (Action::Cancel, "cancel")],
+        Position::new(inner.x, inner.y + 2),
+    );
+    let content = vec![Line::from(input), Line::from(""), hint];
+    f.render_widget(Paragraph::new(content).block(modal_block), modal_area);
+    f.set_cursor_position(Position::new(inner.x + cursor_offset, inner.y));
+    clip_targets(buttons, inner)
+}
+
+/// Renders a modal list of choices with the filter typed to narrow them, returning the areas
+/// of its buttons and of the rows shown
+#[allow(clippy::type_complexity)]
+fn render_picker_modal(
+    f: &mut Frame,
+    app: &App,
+    picker: &Picker,
+) -> (
+    Vec<(ratatui::layout::Rect, Action)>,
+    Vec<(ratatui::layout::Rect, usize)>,
+) {
+    let theme = &app.theme;
+    let area = f.area();
+    let matches: Vec<&PickerItem> = picker.matches().collect();
+
+    // Create a centered modal area that fits the choices, up to a screenful
+    let modal_width = 70.min(area.width);
+    let rows = matches.len() + usize::from(picker.new_label().is_some());
+    let modal_height = (rows.max(1) as u16 + 6).min(area.height.saturating_sub(2));
+    let modal_area = ratatui::layout::Rect {
+        x: (area.width.saturating_sub(modal_width)) / 2,
+        y: (area.height.saturating_sub(modal_height)) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+
+    // Clear the area behind the modal
+    f.render_widget(Clear, modal_area);
+
+    let modal_block = Block::default()
+        .title(format!(" {} ", picker.title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.modal_border))
+        .style(Style::default().bg(theme.modal_background));
+    let inner = modal_block.inner(modal_area);
+    f.render_widget(modal_block, modal_area);
+
+    let layout = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Min(1),
+        Constraint::Length(2),
+    ])
+    .split(inner);
+
+    // The filter, with the cursor in it
+    let (filter, cursor_offset) = visible_input(&picker.filter, layout[0].width.saturating_sub(2));
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.accent)),
+            Span::raw(filter),
+        ])),
+        layout[0],
+    );
+    f.set_cursor_position(Position::new(layout[0].x + 2 + cursor_offset, layout[0].y));
+
+    let mut items: Vec<ListItem> = matches
+        .iter()
+        .map(|item| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    item.label.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("  "),
+                Span::styled(item.detail.clone(), Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+    if let Some(label) = picker.new_label() {
+        let text = format!(
+            "Press {} to create \"{}\"",
+            app.keymap.first_key(KeyMode::Picker, Action::Confirm),
+            label
+        );
+        items.insert(
+            0,
+            ListItem::new(Span::styled(text, Style::default().fg(theme.muted))),
+        );
+    }
+    let mut list_state = ListState::default();
+    list_state.select((!items.is_empty()).then_some(picker.selected()));
+    let row_count = items.len();
+    if items.is_empty() {
+        items.push(ListItem::new(Span::styled(
+            "No matches",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    f.render_stateful_widget(
+        List::new(items).highlight_style(theme.selection()),
+        layout[1],
+        &mut list_state,
+    );
+    let rows = (list_state.offset()..row_count)
+        .zip(layout[1].rows())
+        .map(|(row, area)| (area, row))
+        .collect();
+
+    let hints: &[(Action, &str)] = match picker.kind {
+        PickerKind::SavedSearch => &[
+            (Action::Confirm, "choose"),
+            (Action::RemoveEntry, "remove"),
+            (Action::Cancel, "close"),
+        ],
+        PickerKind::Collection(_) => &[
+            (Action::Confirm, "add or remove"),
+            (Action::Cancel, "close"),
+        ],
+        PickerKind::Command => &[(Action::Confirm, "run"), (Action::Cancel, "close")],
+    };
+    let (hint, buttons) = modal_hint(
+        app,
+        KeyMode::Picker,
+        hints,
+        Position::new(layout[2].x, layout[2].y + 1),
+    );
+    f.render_widget(Paragraph::new(vec![Line::from(""), hint]), layout[2]);
+    (clip_targets(buttons, layout[2]), rows)
+}
+
+/// Gets the part of a text input that fits the width, scrolled to keep the cursor in view,
+/// along with the cursor's offset into it
+fn visible_input(input: &LineEditor, width: u16) -> (String, u16) {
+    let before: Vec<char> = input.text_before_cursor().chars().collect();
+    let mut start = 0;
+    while start < before.len()
+        && Span::raw(before[start..].iter().collect::<String>()).width() >= width as usize
+    {
+        start += 1;
+    }
+    let visible: String = input.text().chars().skip(start).collect();
+    let cursor_offset = Span::raw(before[start..].iter().collect::<String>()).width() as u16;
+    (visible, cursor_offset)
+}
+
+/// Creates a line of key hints for a modal, such as "Enter to confirm, Esc to cancel",
+/// along with the area of each hint when the line is drawn at the given position
+fn modal_hint<'a>(
+    app: &App,
+    mode: KeyMode,
+    hints: &[(Action, &str)],
+    at: Position,
+) -> (Line<'a>, Vec<(ratatui::layout::Rect, Action)>) {
+    let theme = &app.theme;
+    let mut spans = Vec::new();
+    let mut buttons = Vec::new();
+    let mut x = at.x;
+    for (i, (action, hint)) in hints.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(", ", Style::default().fg(theme.muted)));
+            x += 2;
+        }
+        let key = Span::styled(
+            app.keymap.first_key(mode, *action),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        );
+        let text = Span::styled(format!(" to {}", hint), Style::default().fg(theme.muted));
+        let width = (key.width() + text.width()) as u16;
+        buttons.push((ratatui::layout::Rect::new(x, at.y, width, 1), *action));
+        x += width;
+        spans.push(key);
+        spans.push(text);
+    }
+    (Line::from(spans), buttons)
+}
+
+/// Keeps the parts of button areas that are inside the area they were drawn in
+fn clip_targets(
+    buttons: Vec<(ratatui::layout::Rect, Action)>,
+    area: ratatui::layout::Rect,
+) -> Vec<(ratatui::layout::Rect, Action)> {
+    buttons
+        .into_iter()
+        .map(|(button, action)| (button.intersection(area), action))
+        .filter(|(button, _)| !button.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::entities::package_info::PackageInfo;
+    use crate::repository::HomebrewRepository;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    /// Renders the whole screen and returns its text, one string per row
+    fn render(app: &mut App, width: u16, height: u16) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| render_ui(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_help_overlay_scrolls_on_a_short_terminal() {
+        let repository = HomebrewRepository::from_packages(Vec::new());
+        let mut app = App::new(repository, Config::default()).unwrap();
+        app.show_help(KeyMode::Normal);
+        let descriptions: Vec<&str> = app
+            .keymap
+            .bindings(KeyMode::Normal)
+            .iter()
+            .map(|binding| binding.action.description())
+            .collect();
+        let (first, last, bindings) = (
+            descriptions[0],
+            descriptions[descriptions.len() - 1],
+            descriptions.len(),
+        );
+
+        let screen = render(&mut app, 80, 24).join("\n");
+        assert_eq!(app.help_rows, 20);
+        assert!(app.help_scrolls());
+        assert!(screen.contains(first));
+        assert!(screen.contains(&format!(" 1-20 of {} ", bindings)));
+        assert!(screen.contains("press any other key or click to close"));
+        assert!(!screen.contains(last));
+
+        app.scroll_help(isize::MAX);
+        let screen = render(&mut app, 80, 24).join("\n");
+        assert_eq!(app.help_scroll, bindings - 20);
+        assert!(screen.contains(&format!(" {}-{} of {} ", bindings - 19, bindings, bindings)));
+        assert!(screen.contains(last));
+        assert!(!screen.contains(first));
+
+        // Everything fits on a tall terminal
+        let screen = render(&mut app, 80, 60).join("\n");
+        assert!(!app.help_scrolls());
+        assert!(screen.contains(first) && screen.contains(last));
+        assert!(screen.contains("Press any key or click to close"));
+    }
+
+    #[test]
+    fn test_navigation_hints_follow_the_keymap() {
+        let mut config = Config::default();
+        config
+            .keys
+            .normal
+            .insert("move_down".to_string(), vec!["alt-j".to_string()]);
+        config
+            .keys
+            .normal
+            .insert("move_up".to_string(), vec!["alt-k".to_string()]);
+        let mut app = App::new(HomebrewRepository::from_packages(Vec::new()), config).unwrap();
+
+        let screen = render(&mut app, 200, 24).join("\n");
+        assert!(screen.contains("Navigate: Alt-k/Alt-j ←/→ PgUp/PgDn Home/End |"));
+        assert_eq!(
+            navigation_keys(&app.keymap, KeyMode::Timeline, &NAVIGATION),
+            "↑/↓ PgUp/PgDn Home/End"
+        );
+    }
+
+    /// Finds the column and row where a text starts on the rendered screen
+    fn find(screen: &[String], text: &str) -> (u16, u16) {
+        screen
+            .iter()
+            .enumerate()
+            .find_map(|(y, line)| {
+                let byte = line.find(text)?;
+                Some((line[..byte].chars().count() as u16, y as u16))
+            })
+            .unwrap()
+    }
+
+    fn click(app: &mut App, (column, row): (u16, u16)) {
+        use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+        let mouse = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        crate::events::handle_mouse_event(app, mouse).unwrap();
+    }
+
+    #[test]
+    fn test_clicks_choose_picker_rows_and_press_modal_hints() {
+        let repository = HomebrewRepository::from_packages(vec![PackageInfo::fixture("jq")]);
+        let mut app = App::new(repository, Config::default()).unwrap();
+
+        app.show_command_palette();
+        if let ModalState::Picker(picker) = &mut app.modal_state {
+            picker.edit_filter(|filter| filter.set_text("timeline"));
+        }
+        let screen = render(&mut app, 100, 30);
+        click(&mut app, find(&screen, "Show or hide the install timeline"));
+        assert_eq!(app.modal_state, ModalState::None);
+        assert!(app.screen == Screen::Timeline);
+
+        app.show_command_palette();
+        let screen = render(&mut app, 100, 30);
+        click(&mut app, find(&screen, "Esc to close"));
+        assert_eq!(app.modal_state, ModalState::None);
+    }
 }
@@ -0,0 +1,48 @@
+use crate::data_store;
+use crate::sort::SortMode;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+const STATE_FILE_NAME: &str = "state.json";
+
+/// Choices made in the UI that are remembered between sessions
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub sort_mode: SortMode,
+}
+
+impl UiState {
+    /// Loads the remembered state, or the defaults if there is none or it can't be read.
+    /// Losing the state only forgets the sort order, so it is not worth stopping for.
+    pub fn load() -> Self {
+        data_store::load_json(STATE_FILE_NAME).unwrap_or_default()
+    }
+
+    /// Persists the state
+    pub fn save(&self) -> Result<()> {
+        data_store::save_json(STATE_FILE_NAME, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_round_trip() {
+        let state = UiState {
+            sort_mode: SortMode::InstallDate,
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, r#"{"sort_mode":"install-date"}"#);
+        assert_eq!(serde_json::from_str::<UiState>(&json).unwrap(), state);
+    }
+
+    #[test]
+    fn test_unknown_fields_and_missing_state_use_defaults() {
+        let state: UiState = serde_json::from_str(r#"{"future_setting":1}"#).unwrap();
+        assert_eq!(state, UiState::default());
+    }
+}